@@ -0,0 +1,10 @@
+extern crate cc;
+
+fn main() {
+    // `va_list` can only be passed on portably from C.
+    cc::Build::new()
+        .file("src/hooks/vfprintf.c")
+        .compile("jni_hooks");
+
+    println!("cargo:rerun-if-changed=src/hooks/vfprintf.c");
+}
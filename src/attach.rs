@@ -0,0 +1,229 @@
+//! Thread attachment
+
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::ptr;
+
+use consts::*;
+use env::Env;
+use error::{check_version_result, JniError};
+use ffi;
+use ffi::{JavaVM, JavaVMAttachArgs, JNIEnv};
+use objects::AutoLocal;
+use refs::GlobalRef;
+use version::JniVersion;
+use vm::JavaVm;
+
+const THREAD_GROUP_CLASS:&str = "java/lang/ThreadGroup";
+
+thread_local! {
+    static THREAD_ATTACHMENT: RefCell<Option<AttachGuard>> = const { RefCell::new(None) };
+}
+
+/// Returns the `JNIEnv` of the current thread, if it is attached to `jvm`.
+///
+/// Fails with `JniError::Detached` if the thread is not attached, and with
+/// `JniError::UnsupportedVersion` if the VM does not support `version`.
+///
+/// # Safety
+///
+/// `jvm` must point to a valid Java VM.
+pub unsafe fn get_env(jvm:*mut JavaVM, version:JniVersion) -> Result<*mut JNIEnv, JniError> {
+    let (result, env) = ffi::get_env(&*jvm, version.to_jint());
+
+    check_version_result(result, version.to_jint())?;
+
+    Ok(env)
+}
+
+/// Returns the `JNIEnv` of the current thread, attaching the thread first if
+/// needed.
+///
+/// Threads attached by this function stay attached, and are detached when
+/// they exit. This makes it cheap to call from callbacks which arrive on
+/// arbitrary native threads.
+///
+/// # Safety
+///
+/// `jvm` must point to a valid Java VM, which must outlive every thread
+/// attached by this function.
+pub unsafe fn get_env_or_attach(jvm:*mut JavaVM, args:&AttachArgs) -> Result<*mut JNIEnv, JniError> {
+    match get_env(jvm, args.version) {
+        Err(JniError::Detached) => {},
+        result => return result
+    }
+
+    let attachment = AttachGuard::new(jvm, args)?;
+    let env = attachment.as_raw();
+
+    THREAD_ATTACHMENT.with(|cell| {
+        *cell.borrow_mut() = Some(attachment);
+    });
+
+    Ok(env)
+}
+
+/// Options used to attach the current thread to a Java VM.
+pub struct AttachArgs {
+    version: JniVersion,
+    name: Option<String>,
+    group: Option<GlobalRef>,
+    daemon: bool
+}
+
+impl Default for AttachArgs {
+    fn default() -> AttachArgs {
+        AttachArgs::new()
+    }
+}
+
+impl AttachArgs {
+    pub fn new() -> AttachArgs {
+        AttachArgs {
+            version: JniVersion::V1_6,
+            name: None,
+            group: None,
+            daemon: false
+        }
+    }
+
+    /// Sets the JNI version requested for the thread's `JNIEnv`. Defaults to 1.6.
+    pub fn version(mut self, version:JniVersion) -> AttachArgs {
+        self.version = version;
+        self
+    }
+
+    /// Sets the name of the `java.lang.Thread` representing this thread.
+    pub fn name<S: Into<String>>(mut self, name:S) -> AttachArgs {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the `java.lang.ThreadGroup` the thread is added to. Fails with
+    /// `JniError::WrongType` if `group` is not a thread group.
+    pub fn group(mut self, env:&Env, group:&GlobalRef) -> Result<AttachArgs, JniError> {
+        let class = AutoLocal::new(env, env.find_class(THREAD_GROUP_CLASS)?);
+
+        match env.is_instance_of(group.as_obj(), &class)? {
+            true => self.group = Some(group.clone()),
+            false => return Err(JniError::WrongType(THREAD_GROUP_CLASS))
+        }
+
+        Ok(self)
+    }
+
+    /// Attaches the thread as a daemon thread, which does not keep the VM from
+    /// shutting down.
+    pub fn daemon(mut self, daemon:bool) -> AttachArgs {
+        self.daemon = daemon;
+        self
+    }
+}
+
+/// Keeps the current thread attached to a Java VM.
+///
+/// The thread is detached when the guard is dropped, unless it was already
+/// attached when the guard was created. Once the VM has been shut down, the
+/// guard neither hands out its `Env` nor detaches.
+pub struct AttachGuard {
+    vm: JavaVm,
+    env: *mut JNIEnv,
+    detach: bool
+}
+
+impl Drop for AttachGuard {
+    fn drop(&mut self) {
+        if self.detach && self.vm.is_alive() {
+            unsafe {
+                ffi::detach_current_thread(&*self.vm.as_raw());
+            }
+        }
+    }
+}
+
+impl AttachGuard {
+    /// Attaches the current thread to `jvm`, or reuses the existing attachment.
+    ///
+    /// # Safety
+    ///
+    /// `jvm` must point to a valid Java VM which supports `args.version`. It
+    /// must outlive the guard, unless it is shut down through a `JavaVm`.
+    pub unsafe fn new(jvm:*mut JavaVM, args:&AttachArgs) -> Result<AttachGuard, JniError> {
+        AttachGuard::attach(&JavaVm::from_raw(jvm, args.version), args)
+    }
+
+    /// Attaches the current thread to a VM which is alive.
+    pub(crate) unsafe fn attach(vm:&JavaVm, args:&AttachArgs) -> Result<AttachGuard, JniError> {
+        let jvm = vm.as_raw();
+        let (result, env) = ffi::get_env(&*jvm, args.version.to_jint());
+
+        if result == JNI_OK {
+            return Ok(AttachGuard { vm: vm.clone(), env, detach: false });
+        }
+
+        let name = match args.name {
+            Some(ref name) => match CString::new(name.as_str()) {
+                Ok(name) => Some(name),
+                Err(_) => return Err(JniError::InvalidArgument(format!("thread name '{}' contains a NUL byte", name)))
+            },
+            None => None
+        };
+
+        let group = match args.group {
+            Some(ref group) if group.vm().as_raw() != jvm =>
+                return Err(JniError::InvalidArgument(String::from("thread group belongs to another Java VM"))),
+            Some(ref group) => group.as_raw(),
+            None => JNI_NULL
+        };
+
+        let mut attach_args = JavaVMAttachArgs {
+            version: args.version.to_jint(),
+            name: name.as_ref().map_or(ptr::null(), |name| name.as_ptr()),
+            group
+        };
+
+        let (result, env) = if args.daemon {
+            ffi::attach_current_thread_as_daemon(&*jvm, &mut attach_args)
+        } else {
+            ffi::attach_current_thread(&*jvm, &mut attach_args)
+        };
+
+        check_version_result(result, attach_args.version)?;
+
+        Ok(AttachGuard { vm: vm.clone(), env, detach: true })
+    }
+
+    /// Returns the `JNIEnv` of the attached thread. Fails with
+    /// `JniError::EnvInUse` while another `Env` of the thread is alive, e.g.
+    /// one of another guard, and with `JniError::NoJavaVm` once the VM has
+    /// been shut down.
+    pub fn env(&mut self) -> Result<Env<'_>, JniError> {
+        if !self.vm.is_alive() {
+            return Err(JniError::NoJavaVm);
+        }
+
+        unsafe { Env::from_raw(self.env) }.claim()
+    }
+
+    /// Returns the `JNIEnv` of the attached thread, even if another `Env` of
+    /// the thread is alive.
+    ///
+    /// # Safety
+    ///
+    /// Local references made through the returned `Env` must not outlive the
+    /// call they are made in, as the current frame may belong to another `Env`.
+    pub(crate) unsafe fn env_unchecked(&self) -> Env<'_> {
+        Env::from_raw(self.env)
+    }
+
+    /// Returns the raw `JNIEnv` of the attached thread.
+    pub fn as_raw(&self) -> *mut JNIEnv {
+        self.env
+    }
+
+    /// Returns false if the thread was already attached when the guard was
+    /// created, and thus won't be detached on drop.
+    pub fn detaches_on_drop(&self) -> bool {
+        self.detach
+    }
+}
@@ -0,0 +1,171 @@
+//! JVM creation
+
+use libc::c_char;
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use consts::*;
+use error::JniError;
+use ffi::{JavaVMInitArgs, JavaVMOption};
+use hooks;
+use hooks::{AbortHook, ExitHook, VfprintfHook};
+use types::*;
+use version::JniVersion;
+use JNI;
+
+/// Collects the options used to create a Java VM.
+///
+/// Option strings are owned by the builder, so options can be appended in any
+/// number and order before the VM is created with `build()`.
+pub struct JvmBuilder {
+    libjvm_path: Option<PathBuf>,
+    version: JniVersion,
+    ignore_unrecognized: bool,
+    options: Vec<String>,
+    vfprintf_hook: Option<VfprintfHook>,
+    exit_hook: Option<ExitHook>,
+    abort_hook: Option<AbortHook>
+}
+
+impl Default for JvmBuilder {
+    fn default() -> JvmBuilder {
+        JvmBuilder::new()
+    }
+}
+
+impl JvmBuilder {
+    pub fn new() -> JvmBuilder {
+        JvmBuilder {
+            libjvm_path: None,
+            version: JniVersion::V1_6,
+            ignore_unrecognized: false,
+            options: Vec::new(),
+            vfprintf_hook: None,
+            exit_hook: None,
+            abort_hook: None
+        }
+    }
+
+    /// Sets the path of the JVM library to load. If no path is set, the
+    /// library is searched for with `JNI::discover()`.
+    pub fn libjvm_path<P: AsRef<Path>>(mut self, path:P) -> JvmBuilder {
+        self.libjvm_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the JNI version requested from `JNI_CreateJavaVM`. Defaults to 1.6.
+    ///
+    /// Use `JNI::get_supported_version()` to find the newest version supported
+    /// by a JVM library before creating the VM.
+    pub fn version(mut self, version:JniVersion) -> JvmBuilder {
+        self.version = version;
+        self
+    }
+
+    /// If set, the VM ignores unrecognized non-standard (`-X` and `_`) options.
+    pub fn ignore_unrecognized(mut self, ignore:bool) -> JvmBuilder {
+        self.ignore_unrecognized = ignore;
+        self
+    }
+
+    /// Appends a single VM option, e.g. `-Xmx512m` or `-Djava.class.path=app.jar`.
+    pub fn option<S: Into<String>>(mut self, option:S) -> JvmBuilder {
+        self.options.push(option.into());
+        self
+    }
+
+    /// Appends a list of VM options.
+    pub fn options<I, S>(mut self, options:I) -> JvmBuilder
+        where I: IntoIterator<Item = S>, S: Into<String> {
+        self.options.extend(options.into_iter().map(Into::into));
+        self
+    }
+
+    /// Receives all messages the VM would print with `vfprintf()`, e.g. errors
+    /// about invalid options during startup. Messages may be partial lines.
+    pub fn vfprintf_hook<F>(mut self, hook:F) -> JvmBuilder
+        where F: Fn(&str) + Send + Sync + 'static {
+        self.vfprintf_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Called with the exit code when the VM exits, e.g. by `System.exit()`.
+    /// The process is terminated after the hook returns.
+    pub fn exit_hook<F>(mut self, hook:F) -> JvmBuilder
+        where F: Fn(Jint) + Send + Sync + 'static {
+        self.exit_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Called when the VM aborts. The process is terminated after the hook
+    /// returns.
+    pub fn abort_hook<F>(mut self, hook:F) -> JvmBuilder
+        where F: Fn() + Send + Sync + 'static {
+        self.abort_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Loads the JVM library and creates a Java VM with the collected options.
+    pub fn build(self) -> Result<JNI, JniError> {
+        let mut option_strings:Vec<CString> = Vec::with_capacity(self.options.len());
+
+        for option in self.options {
+            match CString::new(option) {
+                Ok(option) => option_strings.push(option),
+                Err(error) => return Err(JniError::InvalidArgument(format!("VM option '{}' contains a NUL byte",
+                    String::from_utf8_lossy(&error.into_vec()))))
+            }
+        }
+
+        let mut jni = match self.libjvm_path {
+            Some(path) => JNI::new(&path)?,
+            None => JNI::discover()?
+        };
+
+        // The hooks go first, so that they see messages about the other options.
+        let mut vm_options:Vec<JavaVMOption> = Vec::with_capacity(option_strings.len() + 3);
+        let mut replaced = hooks::ReplacedHooks::default();
+
+        if let Some(hook) = self.vfprintf_hook {
+            vm_options.push(JavaVMOption {
+                option_string: b"vfprintf\0".as_ptr() as *const c_char,
+                extra_info: hooks::install_vfprintf_hook(hook, &mut replaced)
+            });
+        }
+
+        if let Some(hook) = self.exit_hook {
+            vm_options.push(JavaVMOption {
+                option_string: b"exit\0".as_ptr() as *const c_char,
+                extra_info: hooks::install_exit_hook(hook, &mut replaced)
+            });
+        }
+
+        if let Some(hook) = self.abort_hook {
+            vm_options.push(JavaVMOption {
+                option_string: b"abort\0".as_ptr() as *const c_char,
+                extra_info: hooks::install_abort_hook(hook, &mut replaced)
+            });
+        }
+
+        vm_options.extend(option_strings.iter().map(|option| JavaVMOption {
+            option_string: option.as_ptr(),
+            extra_info: ptr::null_mut()
+        }));
+
+        let vm_init_args = JavaVMInitArgs {
+            version: self.version.to_jint(),
+            n_options: vm_options.len() as Jint,
+            options: vm_options.as_mut_ptr(),
+            ignore_unrecognized: if self.ignore_unrecognized { JNI_TRUE } else { JNI_FALSE }
+        };
+
+        // The hooks of a VM which already exists stay in place.
+        if let Err(error) = jni.create_java_vm(&vm_init_args, self.version) {
+            replaced.restore();
+            return Err(error);
+        }
+
+        Ok(jni)
+    }
+}
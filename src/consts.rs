@@ -1,9 +1,10 @@
 //! JNI constants
 
+use std::ptr;
+
 use types::*;
 
-#[cfg(target_arch = "x86_64")]
-pub const JNI_NULL:Jpointer     = 0u64;
+pub const JNI_NULL:Jobject      = ptr::null_mut();
 
 pub static JNI_FALSE:Jboolean   = 0;
 pub static JNI_TRUE:Jboolean 	= 1;
@@ -19,4 +20,14 @@ pub const JNI_EINVAL:Jint       = -6;
 pub const JNI_COMMIT:Jint       = 1;
 pub const JNI_ABORT:Jint        = 2;
 
+pub const JNI_VERSION_1_1:Jint  = 0x00010001;
+pub const JNI_VERSION_1_2:Jint  = 0x00010002;
+pub const JNI_VERSION_1_4:Jint  = 0x00010004;
 pub const JNI_VERSION_1_6:Jint  = 0x00010006;
+pub const JNI_VERSION_1_8:Jint  = 0x00010008;
+pub const JNI_VERSION_9:Jint    = 0x00090000;
+pub const JNI_VERSION_10:Jint   = 0x000a0000;
+pub const JNI_VERSION_19:Jint   = 0x00130000;
+pub const JNI_VERSION_20:Jint   = 0x00140000;
+pub const JNI_VERSION_21:Jint   = 0x00150000;
+pub const JNI_VERSION_24:Jint   = 0x00180000;
@@ -0,0 +1,174 @@
+//! JVM library discovery
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(target_os = "linux")]
+const LIBJVM_NAME:&str = "libjvm.so";
+
+#[cfg(target_os = "macos")]
+const LIBJVM_NAME:&str = "libjvm.dylib";
+
+#[cfg(windows)]
+const LIBJVM_NAME:&str = "jvm.dll";
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+const LIBJVM_NAME:&str = "libjvm.so";
+
+#[cfg(target_arch = "x86_64")]
+const JRE_ARCH:&str = "amd64";
+
+#[cfg(target_arch = "x86")]
+const JRE_ARCH:&str = "i386";
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+const JRE_ARCH:&str = env::consts::ARCH;
+
+/// Directories which commonly contain one Java installation per subdirectory.
+const SYSTEM_JVM_DIRS:[&str; 3] = ["/usr/lib/jvm", "/usr/lib64/jvm", "/usr/java"];
+
+/// Where a candidate library path came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateSource {
+    JavaHome,
+    JdkHome,
+    JavaOnPath,
+    SystemInstall,
+    BundledJre
+}
+
+impl fmt::Display for CandidateSource {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            CandidateSource::JavaHome => "JAVA_HOME",
+            CandidateSource::JdkHome => "JDK_HOME",
+            CandidateSource::JavaOnPath => "java on PATH",
+            CandidateSource::SystemInstall => "system installation",
+            CandidateSource::BundledJre => "bundled JRE"
+        })
+    }
+}
+
+/// A JVM library path that was tried, and why it could not be used.
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub path: PathBuf,
+    pub source: CandidateSource,
+    pub error: String
+}
+
+/// Returned by `JNI::discover()` if no usable JVM library was found.
+#[derive(Clone, Debug, Default)]
+pub struct DiscoveryReport {
+    pub candidates: Vec<Candidate>
+}
+
+impl fmt::Display for DiscoveryReport {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        if self.candidates.is_empty() {
+            return f.write_str("No JVM library found: no candidate locations");
+        }
+
+        f.write_str("No JVM library found, candidates tried:")?;
+
+        for candidate in &self.candidates {
+            write!(f, "\n  {} ({}): {}", candidate.path.display(), candidate.source, candidate.error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lists JVM library paths to try, in order of preference.
+///
+/// Java installations are looked up in `JAVA_HOME`, `JDK_HOME`, the `java`
+/// executable on `PATH`, common system directories like `/usr/lib/jvm` and
+/// a `jre` folder next to the current executable.
+pub fn candidates() -> Vec<(PathBuf, CandidateSource)> {
+    let mut homes:Vec<(PathBuf, CandidateSource)> = Vec::new();
+
+    if let Some(home) = env::var_os("JAVA_HOME") {
+        homes.push((PathBuf::from(home), CandidateSource::JavaHome));
+    }
+
+    if let Some(home) = env::var_os("JDK_HOME") {
+        homes.push((PathBuf::from(home), CandidateSource::JdkHome));
+    }
+
+    if let Some(home) = java_home_from_path() {
+        homes.push((home, CandidateSource::JavaOnPath));
+    }
+
+    for dir in SYSTEM_JVM_DIRS.iter() {
+        for home in subdirectories(Path::new(dir)) {
+            homes.push((home, CandidateSource::SystemInstall));
+        }
+    }
+
+    if let Some(dir) = env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) {
+        homes.push((dir.join("jre"), CandidateSource::BundledJre));
+    }
+
+    let mut result:Vec<(PathBuf, CandidateSource)> = Vec::new();
+    let mut seen:Vec<PathBuf> = Vec::new();
+
+    for (home, source) in homes {
+        // canonicalized, so symlinked installations are only tried once
+        let home = fs::canonicalize(&home).unwrap_or(home);
+
+        for path in libjvm_paths(&home) {
+            if !seen.contains(&path) {
+                seen.push(path.clone());
+                result.push((path, source));
+            }
+        }
+    }
+
+    result
+}
+
+/// Library locations relative to a Java home, for the JDK 9+ layout, a
+/// Java 8 JDK (with nested `jre`) and a Java 8 JRE.
+fn libjvm_paths(home:&Path) -> Vec<PathBuf> {
+    if cfg!(windows) {
+        return vec![
+            home.join("bin").join("server").join(LIBJVM_NAME),
+            home.join("jre").join("bin").join("server").join(LIBJVM_NAME)
+        ];
+    }
+
+    vec![
+        home.join("lib").join("server").join(LIBJVM_NAME),
+        home.join("jre").join("lib").join(JRE_ARCH).join("server").join(LIBJVM_NAME),
+        home.join("lib").join(JRE_ARCH).join("server").join(LIBJVM_NAME)
+    ]
+}
+
+/// Resolves the `java` executable on `PATH`, following symlinks such as
+/// `/usr/bin/java -> /etc/alternatives/java`, to the home of its installation.
+fn java_home_from_path() -> Option<PathBuf> {
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    let path = env::var_os("PATH")?;
+
+    env::split_paths(&path)
+        .map(|dir| dir.join(java))
+        .find(|exe| exe.is_file())
+        .and_then(|exe| fs::canonicalize(exe).ok())
+        .and_then(|exe| exe.parent().and_then(Path::parent).map(Path::to_path_buf))
+}
+
+fn subdirectories(dir:&Path) -> Vec<PathBuf> {
+    let mut dirs:Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect(),
+        Err(_) => Vec::new()
+    };
+
+    dirs.sort();
+    dirs
+}
@@ -0,0 +1,259 @@
+//! Borrowed contents of strings and primitive arrays
+//!
+//! The `Get*Chars`, `Get*ArrayElements` and `Get*Critical` functions pin or
+//! copy the contents of a Java object until they are released. The guards in
+//! this module release them when dropped.
+
+use libc::{c_char, c_void};
+use std::ffi::CStr;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::slice;
+
+use consts::*;
+use env::Env;
+use mutf8::from_modified_utf8;
+use sys::JNIEnv;
+use types::*;
+
+/// Calls a release function of the JNI function table. These are safe to
+/// call while an exception is pending.
+macro_rules! release {
+    ($env:expr, $function:ident $(, $arg:expr)*) => {
+        unsafe {
+            let raw = $env.as_raw();
+            ((*(*raw).functions).$function)(raw $(, $arg)*)
+        }
+    }
+}
+
+/// Wraps a pointer returned by the VM as a slice; `len` may be zero.
+unsafe fn as_slice<'a, T>(ptr:*const T, len:usize) -> &'a [T] {
+    match len {
+        0 => &[],
+        _ => slice::from_raw_parts(ptr, len)
+    }
+}
+
+unsafe fn as_mut_slice<'a, T>(ptr:*mut T, len:usize) -> &'a mut [T] {
+    match len {
+        0 => &mut [],
+        _ => slice::from_raw_parts_mut(ptr, len)
+    }
+}
+
+/// The contents of a string in modified UTF-8, see
+/// `Env::get_string_utf_chars()`.
+pub struct StringUtfChars<'a, 'local: 'a> {
+    env: &'a Env<'local>,
+    string: Jstring,
+    chars: *const c_char
+}
+
+impl<'a, 'local> StringUtfChars<'a, 'local> {
+    /// # Safety
+    ///
+    /// `chars` must have been returned by `GetStringUTFChars` for `string`.
+    pub(crate) unsafe fn new(env:&'a Env<'local>, string:Jstring, chars:*const c_char) -> StringUtfChars<'a, 'local> {
+        StringUtfChars { env, string, chars }
+    }
+
+    /// Decodes the contents into a Rust string.
+    pub fn to_string_lossy(&self) -> String {
+        from_modified_utf8(self.to_bytes())
+    }
+}
+
+impl<'a, 'local> Deref for StringUtfChars<'a, 'local> {
+    type Target = CStr;
+
+    fn deref(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.chars) }
+    }
+}
+
+impl<'a, 'local> Drop for StringUtfChars<'a, 'local> {
+    fn drop(&mut self) {
+        release!(self.env, release_string_utf_chars, self.string, self.chars)
+    }
+}
+
+/// The UTF-16 contents of a string, see `Env::get_string_chars()`.
+pub struct StringChars<'a, 'local: 'a> {
+    env: &'a Env<'local>,
+    string: Jstring,
+    chars: *const Jchar,
+    len: usize
+}
+
+impl<'a, 'local> StringChars<'a, 'local> {
+    /// # Safety
+    ///
+    /// `chars` must have been returned by `GetStringChars` for `string`, which
+    /// is `len` characters long.
+    pub(crate) unsafe fn new(env:&'a Env<'local>, string:Jstring, chars:*const Jchar, len:usize) -> StringChars<'a, 'local> {
+        StringChars { env, string, chars, len }
+    }
+
+    /// Decodes the contents into a Rust string.
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf16_lossy(self)
+    }
+}
+
+impl<'a, 'local> Deref for StringChars<'a, 'local> {
+    type Target = [Jchar];
+
+    fn deref(&self) -> &[Jchar] {
+        unsafe { as_slice(self.chars, self.len) }
+    }
+}
+
+impl<'a, 'local> Drop for StringChars<'a, 'local> {
+    fn drop(&mut self) {
+        release!(self.env, release_string_chars, self.string, self.chars)
+    }
+}
+
+/// The UTF-16 contents of a string, which the VM may keep from being moved
+/// by blocking garbage collection. See `Env::get_string_critical()`.
+pub struct StringCritical<'a> {
+    env: &'a Env<'a>,
+    string: Jstring,
+    chars: *const Jchar,
+    len: usize
+}
+
+impl<'a> StringCritical<'a> {
+    /// # Safety
+    ///
+    /// `chars` must have been returned by `GetStringCritical` for `string`,
+    /// which is `len` characters long.
+    pub(crate) unsafe fn new(env:&'a Env<'a>, string:Jstring, chars:*const Jchar, len:usize) -> StringCritical<'a> {
+        StringCritical { env, string, chars, len }
+    }
+
+    /// Decodes the contents into a Rust string.
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf16_lossy(self)
+    }
+}
+
+impl<'a> Deref for StringCritical<'a> {
+    type Target = [Jchar];
+
+    fn deref(&self) -> &[Jchar] {
+        unsafe { as_slice(self.chars, self.len) }
+    }
+}
+
+impl<'a> Drop for StringCritical<'a> {
+    fn drop(&mut self) {
+        release!(self.env, release_string_critical, self.string, self.chars)
+    }
+}
+
+/// `Release<Type>ArrayElements`, which all have the same signature apart from
+/// the element type.
+pub type ReleaseArrayElements<T> = unsafe extern "system" fn(env:*mut JNIEnv, array:Jarray, elems:*mut T, mode:Jint);
+
+/// The elements of a primitive array, see e.g. `Env::get_int_array_elements()`.
+///
+/// Changes are copied back into the array when the guard is dropped, unless
+/// they are discarded.
+pub struct ArrayElements<'a, 'local: 'a, T:'a> {
+    env: &'a Env<'local>,
+    array: Jarray,
+    elems: *mut T,
+    len: usize,
+    release: ReleaseArrayElements<T>
+}
+
+impl<'a, 'local, T> ArrayElements<'a, 'local, T> {
+    /// # Safety
+    ///
+    /// `elems` must have been returned for `array`, which has `len` elements,
+    /// by the `Get<Type>ArrayElements` function that matches `release`.
+    pub(crate) unsafe fn new(env:&'a Env<'local>, array:Jarray, elems:*mut T, len:usize, release:ReleaseArrayElements<T>) -> ArrayElements<'a, 'local, T> {
+        ArrayElements { env, array, elems, len, release }
+    }
+
+    /// Copies the changes back into the array, and keeps the elements.
+    pub fn commit(&self) {
+        unsafe { (self.release)(self.env.as_raw(), self.array, self.elems, JNI_COMMIT) }
+    }
+
+    /// Releases the elements without copying back the changes.
+    pub fn discard(self) {
+        unsafe { (self.release)(self.env.as_raw(), self.array, self.elems, JNI_ABORT) }
+        mem::forget(self);
+    }
+}
+
+impl<'a, 'local, T> Deref for ArrayElements<'a, 'local, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { as_slice(self.elems, self.len) }
+    }
+}
+
+impl<'a, 'local, T> DerefMut for ArrayElements<'a, 'local, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { as_mut_slice(self.elems, self.len) }
+    }
+}
+
+impl<'a, 'local, T> Drop for ArrayElements<'a, 'local, T> {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self.env.as_raw(), self.array, self.elems, 0) }
+    }
+}
+
+/// The elements of a primitive array, which the VM may keep from being moved
+/// by blocking garbage collection. See `Env::get_primitive_array_critical()`.
+///
+/// Changes are copied back into the array when the guard is dropped, unless
+/// they are discarded.
+pub struct ArrayCritical<'a, T:'a> {
+    env: &'a Env<'a>,
+    array: Jarray,
+    elems: *mut T,
+    len: usize
+}
+
+impl<'a, T> ArrayCritical<'a, T> {
+    /// # Safety
+    ///
+    /// `elems` must have been returned by `GetPrimitiveArrayCritical` for
+    /// `array`, which has `len` elements of type `T`.
+    pub(crate) unsafe fn new(env:&'a Env<'a>, array:Jarray, elems:*mut T, len:usize) -> ArrayCritical<'a, T> {
+        ArrayCritical { env, array, elems, len }
+    }
+
+    /// Releases the elements without copying back the changes.
+    pub fn discard(self) {
+        release!(self.env, release_primitive_array_critical, self.array, self.elems as *mut c_void, JNI_ABORT);
+        mem::forget(self);
+    }
+}
+
+impl<'a, T> Deref for ArrayCritical<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { as_slice(self.elems, self.len) }
+    }
+}
+
+impl<'a, T> DerefMut for ArrayCritical<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { as_mut_slice(self.elems, self.len) }
+    }
+}
+
+impl<'a, T> Drop for ArrayCritical<'a, T> {
+    fn drop(&mut self) {
+        release!(self.env, release_primitive_array_critical, self.array, self.elems as *mut c_void, 0)
+    }
+}
@@ -0,0 +1,1067 @@
+//! Safe `JNIEnv` wrapper
+//!
+//! Every method checks for a pending Java exception after calling into the
+//! VM. A pending exception is cleared and returned as `JniError::JavaException`,
+//! together with its class name and message. Its stack trace is only collected
+//! when it is first used.
+//!
+//! Exceptions raised with `throw()` or `throw_new()` are left pending, so that
+//! they reach Java when a native method returns. Until then, only the JNI
+//! functions which are safe to call with a pending exception are made; all
+//! other methods fail with `JniError::ExceptionPending`.
+
+// Method and field IDs are opaque handles that are only passed through to the
+// JVM, they are never dereferenced on the Rust side.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use libc::{c_char, c_void};
+use std::cell::Cell;
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::mem;
+use std::process;
+use std::ptr;
+use std::ptr::NonNull;
+
+use consts::*;
+use elements::*;
+use error::{check_result, JniError};
+use exception::take_exception;
+use ffi::JNIEnv;
+use mutf8::to_modified_utf8;
+use objects::*;
+use sys::JNINativeMethod;
+use types::*;
+use value::JValue;
+use version::JniVersion;
+use vm::JavaVm;
+
+/// The JNI interface of the current thread.
+///
+/// Local references returned by its methods are valid for `'local`, which is
+/// the lifetime of the current local frame. An `Env` is bound to its thread
+/// and cannot be cloned, so that `with_local_frame()` can keep the outer
+/// frame from being used while an inner one is active.
+///
+/// For the same reason, there is at most one `Env` per thread which safe code
+/// can get hold of. `AttachGuard::env()` and `JNI::env()` fail with
+/// `JniError::EnvInUse` while another one is alive on the current thread, or
+/// while the thread runs a native method under `catch_panic()`. The other
+/// constructors are unsafe.
+#[derive(Debug)]
+pub struct Env<'local> {
+    raw: NonNull<JNIEnv>,
+    // The claim on the current thread to restore when dropped, if this `Env`
+    // holds it.
+    restore_claim: Option<bool>,
+    lifetime: PhantomData<&'local ()>
+}
+
+thread_local! {
+    // Whether an `Env` is claimed by safe code on the current thread.
+    static ENV_CLAIMED: Cell<bool> = const { Cell::new(false) };
+}
+
+impl<'local> Drop for Env<'local> {
+    fn drop(&mut self) {
+        if let Some(claimed) = self.restore_claim {
+            let _ = ENV_CLAIMED.try_with(|cell| cell.set(claimed));
+        }
+    }
+}
+
+/// A native method to be registered with `Env::register_natives()`.
+#[derive(Clone, Copy, Debug)]
+pub struct NativeMethod<'a> {
+    pub name: &'a str,
+    pub sig: &'a str,
+    pub fn_ptr: *mut c_void
+}
+
+/// Calls a function of the JNI function table, returning early with
+/// `JniError::ExceptionPending` if an exception is pending.
+macro_rules! jni_call {
+    ($env:expr, $function:ident $(, $arg:expr)*) => {{
+        $env.check_not_pending()?;
+        jni_call_unchecked!($env, $function $(, $arg)*)
+    }}
+}
+
+/// Calls a function of the JNI function table without checking for a pending
+/// exception. Only for the functions which the JNI specification lists as
+/// safe to call while an exception is pending.
+macro_rules! jni_call_unchecked {
+    ($env:expr, $function:ident $(, $arg:expr)*) => {
+        unsafe {
+            ((*$env.raw.as_ref().functions).$function)($env.raw.as_ptr() $(, $arg)*)
+        }
+    }
+}
+
+/// Conversion of Java primitive values to and from the Rust types used in the
+/// method signatures of `Env`, which only differ for `boolean`.
+trait Primitive: Sized {
+    type Raw;
+
+    fn from_raw(raw:Self::Raw) -> Self;
+    fn into_raw(self) -> Self::Raw;
+}
+
+impl Primitive for bool {
+    type Raw = Jboolean;
+
+    fn from_raw(raw:Jboolean) -> bool {
+        raw != JNI_FALSE
+    }
+
+    fn into_raw(self) -> Jboolean {
+        self as Jboolean
+    }
+}
+
+macro_rules! identity_primitives {
+    ($($ty:ty),*) => {
+        $(
+            impl Primitive for $ty {
+                type Raw = $ty;
+
+                fn from_raw(raw:$ty) -> $ty {
+                    raw
+                }
+
+                fn into_raw(self) -> $ty {
+                    self
+                }
+            }
+        )*
+    }
+}
+
+identity_primitives!(Jbyte, Jchar, Jshort, Jint, Jlong, Jfloat, Jdouble);
+
+/// Encodes a name or signature, which must not contain NUL.
+fn to_cstring(value:&str) -> Result<CString, JniError> {
+    match value.contains('\0') {
+        true => Err(JniError::InvalidArgument(format!("'{}' contains a NUL byte", value))),
+        false => Ok(to_modified_utf8(value))
+    }
+}
+
+/// Lengths passed to the VM must fit into a `jsize`.
+fn to_jsize(len:usize) -> Result<Jsize, JniError> {
+    Jsize::try_from(len).map_err(|_| JniError::InvalidArgument(format!("length {} exceeds the range of jsize", len)))
+}
+
+fn to_jvalues(args:&[JValue]) -> Vec<Jvalue> {
+    args.iter().map(|arg| arg.to_jvalue()).collect()
+}
+
+/// Pops a pushed local reference frame when dropped, also if the closure
+/// running in it panics.
+struct PushedFrame<'a, 'local: 'a> {
+    env: &'a Env<'local>
+}
+
+impl<'a, 'local> PushedFrame<'a, 'local> {
+    /// Pops the frame, moving `result` into the enclosing one.
+    fn pop(self, result:Jobject) -> Jobject {
+        let result = jni_call_unchecked!(self.env, pop_local_frame, result);
+        mem::forget(self);
+        result
+    }
+}
+
+impl<'a, 'local> Drop for PushedFrame<'a, 'local> {
+    fn drop(&mut self) {
+        jni_call_unchecked!(self.env, pop_local_frame, JNI_NULL);
+    }
+}
+
+impl<'local> Env<'local> {
+    /// Wraps a raw `JNIEnv` pointer, e.g. the one passed to a native method.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be the non-null `JNIEnv` of the current thread, which stays
+    /// valid for `'local`.
+    pub unsafe fn from_raw(raw:*mut JNIEnv) -> Env<'local> {
+        Env { raw: NonNull::new_unchecked(raw), restore_claim: None, lifetime: PhantomData }
+    }
+
+    /// Makes this the only `Env` of the current thread which safe code can
+    /// get, until it is dropped. Fails with `JniError::EnvInUse` if another
+    /// one already is.
+    pub(crate) fn claim(mut self) -> Result<Env<'local>, JniError> {
+        match ENV_CLAIMED.with(|cell| cell.replace(true)) {
+            true => Err(JniError::EnvInUse),
+            false => {
+                self.restore_claim = Some(false);
+                Ok(self)
+            }
+        }
+    }
+
+    /// Wraps the `JNIEnv` passed to a native method, which claims the current
+    /// thread for the duration of the call, even if an outer `Env` does.
+    ///
+    /// # Safety
+    ///
+    /// See `from_raw()`.
+    pub(crate) unsafe fn for_native_method(raw:*mut JNIEnv) -> Env<'local> {
+        let claimed = ENV_CLAIMED.with(|cell| cell.replace(true));
+
+        Env { raw: NonNull::new_unchecked(raw), restore_claim: Some(claimed), lifetime: PhantomData }
+    }
+
+    pub fn as_raw(&self) -> *mut JNIEnv {
+        self.raw.as_ptr()
+    }
+
+    fn check_not_pending(&self) -> Result<(), JniError> {
+        match self.exception_check() {
+            true => Err(JniError::ExceptionPending),
+            false => Ok(())
+        }
+    }
+
+    /// Clears a pending exception and returns it as `JniError::JavaException`.
+    fn check_exception(&self) -> Result<(), JniError> {
+        match take_exception(self)? {
+            Some(exception) => Err(JniError::JavaException(Box::new(exception))),
+            None => Ok(())
+        }
+    }
+
+    /// Checks for a pending exception, then for a null reference.
+    fn check_reference<T:Downcast<'local>>(&self, raw:Jobject, function:&'static str) -> Result<T, JniError> {
+        self.check_exception()?;
+
+        match unsafe { JObject::from_raw(raw) } {
+            Some(obj) => Ok(unsafe { T::from_object_unchecked(obj) }),
+            None => Err(JniError::NullResult(function))
+        }
+    }
+
+    /// Checks for a pending exception, then for a null ID.
+    fn check_id<T>(&self, id:*mut T, function:&'static str) -> Result<*mut T, JniError> {
+        self.check_exception()?;
+
+        match is_null(id) {
+            true => Err(JniError::NullResult(function)),
+            false => Ok(id)
+        }
+    }
+
+    /// Checks for a pending exception, then wraps a nullable reference.
+    fn check_nullable(&self, raw:Jobject) -> Result<Option<JObject<'local>>, JniError> {
+        self.check_exception()?;
+
+        Ok(unsafe { JObject::from_raw(raw) })
+    }
+
+    /// Fails with `JniError::UnsupportedVersion` if a function was added in a
+    /// newer JNI version than the VM supports, and is missing from its table.
+    fn require_version(&self, version:JniVersion) -> Result<(), JniError> {
+        match self.get_version()? >= version {
+            true => Ok(()),
+            false => Err(JniError::UnsupportedVersion(version.to_jint()))
+        }
+    }
+
+    // Version information
+
+    pub fn get_version(&self) -> Result<JniVersion, JniError> {
+        let version = jni_call!(self, get_version);
+        JniVersion::supported_by(version).ok_or(JniError::UnsupportedVersion(version))
+    }
+
+    // Class operations
+
+    /// Defines a class from the bytes of a class file. A `name` of `None`
+    /// takes the name from the class file.
+    pub fn define_class(&self, name:Option<&str>, loader:Option<&JObject>, bytes:&[u8]) -> Result<JClass<'local>, JniError> {
+        let name = match name {
+            Some(name) => Some(to_cstring(name)?),
+            None => None
+        };
+        let name_ptr = name.as_ref().map_or(ptr::null(), |name| name.as_ptr());
+
+        let class = jni_call!(self, define_class, name_ptr, as_raw_or_null(loader), bytes.as_ptr() as *const Jbyte, to_jsize(bytes.len())?);
+
+        self.check_reference(class, "DefineClass")
+    }
+
+    pub fn find_class(&self, name:&str) -> Result<JClass<'local>, JniError> {
+        let name = to_cstring(name)?;
+
+        let class = jni_call!(self, find_class, name.as_ptr());
+
+        self.check_reference(class, "FindClass")
+    }
+
+    /// Returns `None` for `java.lang.Object`, interfaces and primitive types.
+    pub fn get_superclass(&self, class:&JClass) -> Result<Option<JClass<'local>>, JniError> {
+        let superclass = jni_call!(self, get_superclass, class.as_raw());
+
+        self.check_exception()?;
+
+        Ok(unsafe { JClass::from_raw(superclass) })
+    }
+
+    pub fn is_assignable_from(&self, sub:&JClass, sup:&JClass) -> Result<bool, JniError> {
+        let result = jni_call!(self, is_assignable_from, sub.as_raw(), sup.as_raw());
+
+        self.check_exception()?;
+
+        Ok(bool::from_raw(result))
+    }
+
+    /// Returns the module of a class. Requires JNI 9.
+    pub fn get_module(&self, class:&JClass) -> Result<JObject<'local>, JniError> {
+        self.require_version(JniVersion::V9)?;
+
+        let module = jni_call!(self, get_module, class.as_raw());
+
+        self.check_reference(module, "GetModule")
+    }
+
+    // Reflection support
+
+    pub fn from_reflected_method(&self, method:&JObject) -> Result<JmethodID, JniError> {
+        let id = jni_call!(self, from_reflected_method, method.as_raw());
+
+        self.check_id(id, "FromReflectedMethod")
+    }
+
+    pub fn from_reflected_field(&self, field:&JObject) -> Result<JfieldID, JniError> {
+        let id = jni_call!(self, from_reflected_field, field.as_raw());
+
+        self.check_id(id, "FromReflectedField")
+    }
+
+    pub fn to_reflected_method(&self, class:&JClass, method:JmethodID, is_static:bool) -> Result<JObject<'local>, JniError> {
+        let obj = jni_call!(self, to_reflected_method, class.as_raw(), method, is_static.into_raw());
+
+        self.check_reference(obj, "ToReflectedMethod")
+    }
+
+    pub fn to_reflected_field(&self, class:&JClass, field:JfieldID, is_static:bool) -> Result<JObject<'local>, JniError> {
+        let obj = jni_call!(self, to_reflected_field, class.as_raw(), field, is_static.into_raw());
+
+        self.check_reference(obj, "ToReflectedField")
+    }
+
+    // Exceptions
+
+    /// Throws an exception, which is left pending.
+    pub fn throw(&self, throwable:&JThrowable) -> Result<(), JniError> {
+        check_result(jni_call!(self, throw, throwable.as_raw()))
+    }
+
+    /// Throws a new instance of the class `class_name`, which must have a
+    /// constructor taking a message string. The exception is left pending.
+    pub fn throw_new(&self, class_name:&str, message:&str) -> Result<(), JniError> {
+        let class = AutoLocal::new(self, self.find_class(class_name)?);
+        let message = to_modified_utf8(message);
+
+        check_result(jni_call!(self, throw_new, class.as_raw(), message.as_ptr()))
+    }
+
+    /// Reports a fatal error and aborts the VM.
+    pub fn fatal_error(&self, message:&str) -> ! {
+        let message = to_modified_utf8(message);
+
+        jni_call_unchecked!(self, fatal_error, message.as_ptr());
+
+        // FatalError does not return.
+        process::abort()
+    }
+
+    pub fn exception_occurred(&self) -> Option<JThrowable<'local>> {
+        let throwable = jni_call_unchecked!(self, exception_occurred);
+
+        unsafe { JThrowable::from_raw(throwable) }
+    }
+
+    /// Prints the pending exception to the standard error stream, and clears it.
+    pub fn exception_describe(&self) {
+        jni_call_unchecked!(self, exception_describe)
+    }
+
+    pub fn exception_clear(&self) {
+        jni_call_unchecked!(self, exception_clear)
+    }
+
+    pub fn exception_check(&self) -> bool {
+        bool::from_raw(jni_call_unchecked!(self, exception_check))
+    }
+
+    // Local references and frames
+
+    /// Returns the raw global reference, which is owned by a `GlobalRef`.
+    pub(crate) fn new_global_ref(&self, obj:&JObject) -> Result<Jobject, JniError> {
+        let global = jni_call!(self, new_global_ref, obj.as_raw());
+
+        self.check_id(global, "NewGlobalRef")
+    }
+
+    pub(crate) fn delete_global_ref(&self, global:Jobject) {
+        jni_call_unchecked!(self, delete_global_ref, global)
+    }
+
+    /// Returns the raw weak global reference, which is owned by a `WeakRef`.
+    pub(crate) fn new_weak_global_ref(&self, obj:&JObject) -> Result<Jweak, JniError> {
+        let weak = jni_call!(self, new_weak_global_ref, obj.as_raw());
+
+        self.check_id(weak, "NewWeakGlobalRef")
+    }
+
+    pub(crate) fn delete_weak_global_ref(&self, weak:Jweak) {
+        jni_call_unchecked!(self, delete_weak_global_ref, weak)
+    }
+
+    /// Returns `None` if `obj` is a weak reference to a collected object.
+    pub fn new_local_ref(&self, obj:&JObject) -> Result<Option<JObject<'local>>, JniError> {
+        let local = jni_call!(self, new_local_ref, obj.as_raw());
+
+        self.check_nullable(local)
+    }
+
+    pub fn delete_local_ref(&self, obj:JObject) {
+        jni_call_unchecked!(self, delete_local_ref, obj.as_raw())
+    }
+
+    /// The VM may leave an OutOfMemoryError pending when it refuses to
+    /// reserve local references.
+    fn check_capacity(&self, result:Jint, capacity:Jint) -> Result<(), JniError> {
+        self.check_exception()?;
+
+        match result {
+            JNI_OK => Ok(()),
+            _ => Err(JniError::LocalCapacity(capacity))
+        }
+    }
+
+    /// Makes sure that at least `capacity` more local references can be
+    /// created in the current frame.
+    pub fn ensure_local_capacity(&self, capacity:Jint) -> Result<(), JniError> {
+        let result = jni_call!(self, ensure_local_capacity, capacity);
+
+        self.check_capacity(result, capacity)
+    }
+
+    /// Runs `f` in a new local reference frame, which has room for at least
+    /// `capacity` local references.
+    ///
+    /// All local references created by `f` are freed when it returns, except
+    /// the object it returns, which is moved into the current frame. Fails
+    /// with `JniError::ExceptionPending` without pushing a frame if an
+    /// exception is already pending, which is left pending.
+    pub fn with_local_frame<F>(&mut self, capacity:Jint, f:F) -> Result<Option<JObject<'local>>, JniError>
+        where F: for<'frame> FnOnce(&mut Env<'frame>) -> Result<Option<JObject<'frame>>, JniError>
+    {
+        let result = jni_call!(self, push_local_frame, capacity);
+        self.check_capacity(result, capacity)?;
+
+        let frame = PushedFrame { env: self };
+        let mut env = Env { raw: self.raw, restore_claim: None, lifetime: PhantomData };
+
+        // On an error, the frame is popped without keeping an object.
+        let result = f(&mut env)?;
+        let result = frame.pop(as_raw_or_null(result.as_ref()));
+
+        Ok(unsafe { JObject::from_raw(result) })
+    }
+
+    /// Runs `f` in a new local reference frame like `with_local_frame()`, but
+    /// only borrows the env, so that it can be used while checking for
+    /// exceptions. `R` cannot name `'frame`, so no reference made through the
+    /// frame env escapes; `f` must not make local references through `self`.
+    pub(crate) fn with_frame<R, F>(&self, capacity:Jint, f:F) -> Result<R, JniError>
+        where F: for<'frame> FnOnce(&Env<'frame>) -> Result<R, JniError>
+    {
+        let result = jni_call!(self, push_local_frame, capacity);
+        self.check_capacity(result, capacity)?;
+
+        let _frame = PushedFrame { env: self };
+
+        f(&Env { raw: self.raw, restore_claim: None, lifetime: PhantomData })
+    }
+
+    pub fn get_object_ref_type(&self, obj:&JObject) -> Result<JobjectRefType, JniError> {
+        Ok(jni_call!(self, get_object_ref_type, obj.as_raw()))
+    }
+
+    // Object operations
+
+    /// Allocates an object without running any of its constructors.
+    pub fn alloc_object(&self, class:&JClass) -> Result<JObject<'local>, JniError> {
+        let obj = jni_call!(self, alloc_object, class.as_raw());
+
+        self.check_reference(obj, "AllocObject")
+    }
+
+    pub fn new_object_a(&self, class:&JClass, ctor:JmethodID, args:&[JValue]) -> Result<JObject<'local>, JniError> {
+        let obj = jni_call!(self, new_object_a, class.as_raw(), ctor, to_jvalues(args).as_ptr());
+
+        self.check_reference(obj, "NewObjectA")
+    }
+
+    pub fn get_object_class(&self, obj:&JObject) -> Result<JClass<'local>, JniError> {
+        let class = jni_call!(self, get_object_class, obj.as_raw());
+
+        self.check_reference(class, "GetObjectClass")
+    }
+
+    pub fn is_instance_of(&self, obj:&JObject, class:&JClass) -> Result<bool, JniError> {
+        let result = jni_call!(self, is_instance_of, obj.as_raw(), class.as_raw());
+
+        self.check_exception()?;
+
+        Ok(bool::from_raw(result))
+    }
+
+    /// Compares two references, where `None` stands for null.
+    pub fn is_same_object(&self, a:Option<&JObject>, b:Option<&JObject>) -> Result<bool, JniError> {
+        Ok(bool::from_raw(jni_call!(self, is_same_object, as_raw_or_null(a), as_raw_or_null(b))))
+    }
+
+    /// Converts an object into a more specific reference type, failing with
+    /// `JniError::WrongType` if it is not an instance of that type.
+    pub fn downcast<T:Downcast<'local>>(&self, obj:JObject<'local>) -> Result<T, JniError> {
+        let class = self.find_class(T::CLASS_NAME)?;
+        let result = self.is_instance_of(&obj, &class);
+        self.delete_local_ref(class.into());
+
+        match result? {
+            true => Ok(unsafe { T::from_object_unchecked(obj) }),
+            false => Err(JniError::WrongType(T::CLASS_NAME))
+        }
+    }
+
+    // Method and field IDs
+
+    pub fn get_method_id(&self, class:&JClass, name:&str, sig:&str) -> Result<JmethodID, JniError> {
+        let name = to_cstring(name)?;
+        let sig = to_cstring(sig)?;
+
+        let id = jni_call!(self, get_method_id, class.as_raw(), name.as_ptr(), sig.as_ptr());
+
+        self.check_id(id, "GetMethodID")
+    }
+
+    pub fn get_static_method_id(&self, class:&JClass, name:&str, sig:&str) -> Result<JmethodID, JniError> {
+        let name = to_cstring(name)?;
+        let sig = to_cstring(sig)?;
+
+        let id = jni_call!(self, get_static_method_id, class.as_raw(), name.as_ptr(), sig.as_ptr());
+
+        self.check_id(id, "GetStaticMethodID")
+    }
+
+    pub fn get_field_id(&self, class:&JClass, name:&str, sig:&str) -> Result<JfieldID, JniError> {
+        let name = to_cstring(name)?;
+        let sig = to_cstring(sig)?;
+
+        let id = jni_call!(self, get_field_id, class.as_raw(), name.as_ptr(), sig.as_ptr());
+
+        self.check_id(id, "GetFieldID")
+    }
+
+    pub fn get_static_field_id(&self, class:&JClass, name:&str, sig:&str) -> Result<JfieldID, JniError> {
+        let name = to_cstring(name)?;
+        let sig = to_cstring(sig)?;
+
+        let id = jni_call!(self, get_static_field_id, class.as_raw(), name.as_ptr(), sig.as_ptr());
+
+        self.check_id(id, "GetStaticFieldID")
+    }
+
+    // Method calls returning objects or nothing. A null return value is
+    // passed on as `None`.
+
+    pub fn call_object_method_a(&self, obj:&JObject, method:JmethodID, args:&[JValue]) -> Result<Option<JObject<'local>>, JniError> {
+        let result = jni_call!(self, call_object_method_a, obj.as_raw(), method, to_jvalues(args).as_ptr());
+
+        self.check_nullable(result)
+    }
+
+    pub fn call_nonvirtual_object_method_a(&self, obj:&JObject, class:&JClass, method:JmethodID, args:&[JValue]) -> Result<Option<JObject<'local>>, JniError> {
+        let result = jni_call!(self, call_nonvirtual_object_method_a, obj.as_raw(), class.as_raw(), method, to_jvalues(args).as_ptr());
+
+        self.check_nullable(result)
+    }
+
+    pub fn call_static_object_method_a(&self, class:&JClass, method:JmethodID, args:&[JValue]) -> Result<Option<JObject<'local>>, JniError> {
+        let result = jni_call!(self, call_static_object_method_a, class.as_raw(), method, to_jvalues(args).as_ptr());
+
+        self.check_nullable(result)
+    }
+
+    pub fn call_void_method_a(&self, obj:&JObject, method:JmethodID, args:&[JValue]) -> Result<(), JniError> {
+        jni_call!(self, call_void_method_a, obj.as_raw(), method, to_jvalues(args).as_ptr());
+
+        self.check_exception()
+    }
+
+    pub fn call_nonvirtual_void_method_a(&self, obj:&JObject, class:&JClass, method:JmethodID, args:&[JValue]) -> Result<(), JniError> {
+        jni_call!(self, call_nonvirtual_void_method_a, obj.as_raw(), class.as_raw(), method, to_jvalues(args).as_ptr());
+
+        self.check_exception()
+    }
+
+    pub fn call_static_void_method_a(&self, class:&JClass, method:JmethodID, args:&[JValue]) -> Result<(), JniError> {
+        jni_call!(self, call_static_void_method_a, class.as_raw(), method, to_jvalues(args).as_ptr());
+
+        self.check_exception()
+    }
+
+    // Object fields
+
+    pub fn get_object_field(&self, obj:&JObject, field:JfieldID) -> Result<Option<JObject<'local>>, JniError> {
+        let value = jni_call!(self, get_object_field, obj.as_raw(), field);
+
+        self.check_nullable(value)
+    }
+
+    pub fn set_object_field(&self, obj:&JObject, field:JfieldID, value:Option<&JObject>) -> Result<(), JniError> {
+        jni_call!(self, set_object_field, obj.as_raw(), field, as_raw_or_null(value));
+
+        self.check_exception()
+    }
+
+    pub fn get_static_object_field(&self, class:&JClass, field:JfieldID) -> Result<Option<JObject<'local>>, JniError> {
+        let value = jni_call!(self, get_static_object_field, class.as_raw(), field);
+
+        self.check_nullable(value)
+    }
+
+    pub fn set_static_object_field(&self, class:&JClass, field:JfieldID, value:Option<&JObject>) -> Result<(), JniError> {
+        jni_call!(self, set_static_object_field, class.as_raw(), field, as_raw_or_null(value));
+
+        self.check_exception()
+    }
+
+    // String operations
+
+    /// Creates a Java string, which is encoded as UTF-16.
+    pub fn new_string(&self, value:&str) -> Result<JString<'local>, JniError> {
+        let chars:Vec<Jchar> = value.encode_utf16().collect();
+
+        let string = jni_call!(self, new_string, chars.as_ptr(), to_jsize(chars.len())?);
+
+        self.check_reference(string, "NewString")
+    }
+
+    /// Returns the number of UTF-16 code units of a string.
+    pub fn get_string_length(&self, string:&JString) -> Result<Jsize, JniError> {
+        Ok(jni_call!(self, get_string_length, string.as_raw()))
+    }
+
+    /// Creates a Java string from modified UTF-8, which allows NUL characters.
+    pub fn new_string_utf(&self, value:&str) -> Result<JString<'local>, JniError> {
+        let utf = to_modified_utf8(value);
+
+        let string = jni_call!(self, new_string_utf, utf.as_ptr());
+
+        self.check_reference(string, "NewStringUTF")
+    }
+
+    /// Returns the length of a string in modified UTF-8 bytes.
+    pub fn get_string_utf_length(&self, string:&JString) -> Result<Jsize, JniError> {
+        Ok(jni_call!(self, get_string_utf_length, string.as_raw()))
+    }
+
+    /// Returns the length of a string in modified UTF-8 bytes, which may
+    /// exceed `Jsize` for long strings. Requires JNI 24.
+    pub fn get_string_utf_length_as_long(&self, string:&JString) -> Result<Jlong, JniError> {
+        self.require_version(JniVersion::V24)?;
+
+        Ok(jni_call!(self, get_string_utf_length_as_long, string.as_raw()))
+    }
+
+    /// Borrows the contents of a string in modified UTF-8.
+    pub fn get_string_utf_chars<'a>(&'a self, string:&'a JString) -> Result<StringUtfChars<'a, 'local>, JniError> {
+        let chars = jni_call!(self, get_string_utf_chars, string.as_raw(), ptr::null_mut());
+
+        self.check_exception()?;
+
+        match chars.is_null() {
+            true => Err(JniError::NullResult("GetStringUTFChars")),
+            false => Ok(unsafe { StringUtfChars::new(self, string.as_raw(), chars) })
+        }
+    }
+
+    /// Copies `len` characters starting at `start` in modified UTF-8.
+    pub fn get_string_utf_region(&self, string:&JString, start:Jsize, len:Jsize) -> Result<Vec<u8>, JniError> {
+        if len < 0 {
+            return Err(JniError::InvalidArgument(format!("negative length {}", len)));
+        }
+
+        // Each UTF-16 character takes at most three bytes, and the VM may
+        // append a NUL byte.
+        let mut buf:Vec<u8> = vec![0; len as usize * 3 + 1];
+
+        jni_call!(self, get_string_utf_region, string.as_raw(), start, len, buf.as_mut_ptr() as *mut c_char);
+
+        self.check_exception()?;
+
+        // Modified UTF-8 never contains a zero byte.
+        let end = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+        buf.truncate(end);
+
+        Ok(buf)
+    }
+
+    /// Borrows the UTF-16 contents of a string.
+    pub fn get_string_chars<'a>(&'a self, string:&'a JString) -> Result<StringChars<'a, 'local>, JniError> {
+        let len = self.get_string_length(string)?;
+        let chars = jni_call!(self, get_string_chars, string.as_raw(), ptr::null_mut());
+
+        self.check_exception()?;
+
+        match chars.is_null() {
+            true => Err(JniError::NullResult("GetStringChars")),
+            false => Ok(unsafe { StringChars::new(self, string.as_raw(), chars, len as usize) })
+        }
+    }
+
+    /// Borrows the UTF-16 contents of a string, possibly without copying.
+    ///
+    /// No other JNI function may be called until the guard is dropped, which
+    /// is why it borrows the `Env` mutably. Keep the critical region short, as
+    /// the VM may block garbage collection meanwhile.
+    pub fn get_string_critical<'a>(&'a mut self, string:&'a JString) -> Result<StringCritical<'a>, JniError> {
+        let len = self.get_string_length(string)?;
+        let chars = jni_call!(self, get_string_critical, string.as_raw(), ptr::null_mut());
+
+        // Not even `ExceptionCheck` may be called inside the critical region.
+        match chars.is_null() {
+            true => {
+                self.check_exception()?;
+                Err(JniError::NullResult("GetStringCritical"))
+            },
+            false => Ok(unsafe { StringCritical::new(self, string.as_raw(), chars, len as usize) })
+        }
+    }
+
+    /// Copies the contents of a Java string, which is decoded from UTF-16.
+    pub fn get_string(&self, string:&JString) -> Result<String, JniError> {
+        let len = self.get_string_length(string)?;
+        let mut buf:Vec<Jchar> = vec![0; len as usize];
+
+        jni_call!(self, get_string_region, string.as_raw(), 0, len, buf.as_mut_ptr());
+
+        self.check_exception()?;
+
+        Ok(String::from_utf16_lossy(&buf))
+    }
+
+    // Array operations
+
+    pub fn get_array_length(&self, array:&JObject) -> Result<Jsize, JniError> {
+        let len = jni_call!(self, get_array_length, array.as_raw());
+
+        self.check_exception()?;
+
+        Ok(len)
+    }
+
+    /// Borrows the elements of a primitive array, possibly without copying.
+    ///
+    /// No other JNI function may be called until the guard is dropped, which
+    /// is why it borrows the `Env` mutably.
+    pub fn get_primitive_array_critical<'a, A:PrimitiveArray<'local>>(&'a mut self, array:&'a A) -> Result<ArrayCritical<'a, A::Element>, JniError> {
+        let len = self.get_array_length(array)?;
+        let elems = jni_call!(self, get_primitive_array_critical, array.as_raw(), ptr::null_mut());
+
+        // Not even `ExceptionCheck` may be called inside the critical region.
+        match elems.is_null() {
+            true => {
+                self.check_exception()?;
+                Err(JniError::NullResult("GetPrimitiveArrayCritical"))
+            },
+            false => Ok(unsafe { ArrayCritical::new(self, array.as_raw(), elems as *mut A::Element, len as usize) })
+        }
+    }
+
+    /// Creates an array of `len` elements of class `element_class`, all set to `init`.
+    pub fn new_object_array(&self, len:Jsize, element_class:&JClass, init:Option<&JObject>) -> Result<JObjectArray<'local>, JniError> {
+        let array = jni_call!(self, new_object_array, len, element_class.as_raw(), as_raw_or_null(init));
+
+        self.check_reference(array, "NewObjectArray")
+    }
+
+    pub fn get_object_array_element(&self, array:&JObjectArray, index:Jsize) -> Result<Option<JObject<'local>>, JniError> {
+        let element = jni_call!(self, get_object_array_element, array.as_raw(), index);
+
+        self.check_nullable(element)
+    }
+
+    pub fn set_object_array_element(&self, array:&JObjectArray, index:Jsize, value:Option<&JObject>) -> Result<(), JniError> {
+        jni_call!(self, set_object_array_element, array.as_raw(), index, as_raw_or_null(value));
+
+        self.check_exception()
+    }
+
+    // Registering native methods
+
+    /// Registers native method implementations for a class.
+    ///
+    /// # Safety
+    ///
+    /// Each `fn_ptr` must be an `extern "system"` function whose parameters
+    /// match the method signature, preceded by `*mut JNIEnv` and the object
+    /// or class.
+    pub unsafe fn register_natives(&self, class:&JClass, methods:&[NativeMethod]) -> Result<(), JniError> {
+        let mut names:Vec<CString> = Vec::with_capacity(methods.len());
+        let mut sigs:Vec<CString> = Vec::with_capacity(methods.len());
+
+        for method in methods {
+            names.push(to_cstring(method.name)?);
+            sigs.push(to_cstring(method.sig)?);
+        }
+
+        let natives:Vec<JNINativeMethod> = methods.iter().enumerate()
+            .map(|(i, method)| JNINativeMethod {
+                name: names[i].as_ptr() as *mut c_char,
+                signature: sigs[i].as_ptr() as *mut c_char,
+                fn_ptr: method.fn_ptr
+            })
+            .collect();
+
+        let result = jni_call!(self, register_natives, class.as_raw(), natives.as_ptr(), to_jsize(natives.len())?);
+
+        self.check_exception()?;
+        check_result(result)
+    }
+
+    pub fn unregister_natives(&self, class:&JClass) -> Result<(), JniError> {
+        let result = jni_call!(self, unregister_natives, class.as_raw());
+
+        self.check_exception()?;
+        check_result(result)
+    }
+
+    // Monitor operations
+
+    pub fn monitor_enter(&self, obj:&JObject) -> Result<(), JniError> {
+        let result = jni_call!(self, monitor_enter, obj.as_raw());
+
+        self.check_exception()?;
+        check_result(result)
+    }
+
+    /// Monitors can be exited while an exception is pending, which is then
+    /// left in place.
+    pub fn monitor_exit(&self, obj:&JObject) -> Result<(), JniError> {
+        let pending = self.exception_check();
+        let result = jni_call_unchecked!(self, monitor_exit, obj.as_raw());
+
+        if !pending {
+            self.check_exception()?;
+        }
+        check_result(result)
+    }
+
+    // Java VM interface
+
+    pub fn get_java_vm(&self) -> Result<JavaVm, JniError> {
+        let mut jvm = ptr::null_mut();
+
+        check_result(jni_call!(self, get_java_vm, &mut jvm))?;
+
+        Ok(unsafe { JavaVm::from_raw(jvm, self.get_version()?) })
+    }
+
+    // NIO support
+
+    /// Creates a `java.nio.ByteBuffer` for a block of memory.
+    ///
+    /// # Safety
+    ///
+    /// The memory must stay valid and must not be freed while the buffer can
+    /// still be reached from Java.
+    pub unsafe fn new_direct_byte_buffer(&self, address:*mut u8, capacity:usize) -> Result<JObject<'local>, JniError> {
+        let buffer = jni_call!(self, new_direct_byte_buffer, address as *mut c_void, capacity as Jlong);
+
+        self.check_reference(buffer, "NewDirectByteBuffer")
+    }
+
+    pub fn get_direct_buffer_address(&self, buffer:&JObject) -> Result<*mut u8, JniError> {
+        let address = jni_call!(self, get_direct_buffer_address, buffer.as_raw());
+
+        self.check_id(address, "GetDirectBufferAddress").map(|address| address as *mut u8)
+    }
+
+    pub fn get_direct_buffer_capacity(&self, buffer:&JObject) -> Result<Jlong, JniError> {
+        let capacity = jni_call!(self, get_direct_buffer_capacity, buffer.as_raw());
+
+        self.check_exception()?;
+
+        match capacity {
+            -1 => Err(JniError::InvalidArgument("not a direct buffer".to_string())),
+            _ => Ok(capacity)
+        }
+    }
+
+    // Virtual threads
+
+    /// Requires JNI 21.
+    pub fn is_virtual_thread(&self, obj:&JObject) -> Result<bool, JniError> {
+        self.require_version(JniVersion::V21)?;
+
+        let result = jni_call!(self, is_virtual_thread, obj.as_raw());
+
+        self.check_exception()?;
+
+        Ok(bool::from_raw(result))
+    }
+}
+
+/// Method calls and field accesses for a primitive type.
+macro_rules! primitive_members {
+    ($($ty:ty => $call:ident, $call_nonvirtual:ident, $call_static:ident,
+        $get:ident, $set:ident, $get_static:ident, $set_static:ident);*) => {
+        impl<'local> Env<'local> {
+            $(
+                pub fn $call(&self, obj:&JObject, method:JmethodID, args:&[JValue]) -> Result<$ty, JniError> {
+                    let result = jni_call!(self, $call, obj.as_raw(), method, to_jvalues(args).as_ptr());
+
+                    self.check_exception()?;
+
+                    Ok(<$ty>::from_raw(result))
+                }
+
+                pub fn $call_nonvirtual(&self, obj:&JObject, class:&JClass, method:JmethodID, args:&[JValue]) -> Result<$ty, JniError> {
+                    let result = jni_call!(self, $call_nonvirtual, obj.as_raw(), class.as_raw(), method, to_jvalues(args).as_ptr());
+
+                    self.check_exception()?;
+
+                    Ok(<$ty>::from_raw(result))
+                }
+
+                pub fn $call_static(&self, class:&JClass, method:JmethodID, args:&[JValue]) -> Result<$ty, JniError> {
+                    let result = jni_call!(self, $call_static, class.as_raw(), method, to_jvalues(args).as_ptr());
+
+                    self.check_exception()?;
+
+                    Ok(<$ty>::from_raw(result))
+                }
+
+                pub fn $get(&self, obj:&JObject, field:JfieldID) -> Result<$ty, JniError> {
+                    let value = jni_call!(self, $get, obj.as_raw(), field);
+
+                    self.check_exception()?;
+
+                    Ok(<$ty>::from_raw(value))
+                }
+
+                pub fn $set(&self, obj:&JObject, field:JfieldID, value:$ty) -> Result<(), JniError> {
+                    jni_call!(self, $set, obj.as_raw(), field, value.into_raw());
+
+                    self.check_exception()
+                }
+
+                pub fn $get_static(&self, class:&JClass, field:JfieldID) -> Result<$ty, JniError> {
+                    let value = jni_call!(self, $get_static, class.as_raw(), field);
+
+                    self.check_exception()?;
+
+                    Ok(<$ty>::from_raw(value))
+                }
+
+                pub fn $set_static(&self, class:&JClass, field:JfieldID, value:$ty) -> Result<(), JniError> {
+                    jni_call!(self, $set_static, class.as_raw(), field, value.into_raw());
+
+                    self.check_exception()
+                }
+            )*
+        }
+    }
+}
+
+primitive_members! {
+    bool => call_boolean_method_a, call_nonvirtual_boolean_method_a, call_static_boolean_method_a,
+        get_boolean_field, set_boolean_field, get_static_boolean_field, set_static_boolean_field;
+    Jbyte => call_byte_method_a, call_nonvirtual_byte_method_a, call_static_byte_method_a,
+        get_byte_field, set_byte_field, get_static_byte_field, set_static_byte_field;
+    Jchar => call_char_method_a, call_nonvirtual_char_method_a, call_static_char_method_a,
+        get_char_field, set_char_field, get_static_char_field, set_static_char_field;
+    Jshort => call_short_method_a, call_nonvirtual_short_method_a, call_static_short_method_a,
+        get_short_field, set_short_field, get_static_short_field, set_static_short_field;
+    Jint => call_int_method_a, call_nonvirtual_int_method_a, call_static_int_method_a,
+        get_int_field, set_int_field, get_static_int_field, set_static_int_field;
+    Jlong => call_long_method_a, call_nonvirtual_long_method_a, call_static_long_method_a,
+        get_long_field, set_long_field, get_static_long_field, set_static_long_field;
+    Jfloat => call_float_method_a, call_nonvirtual_float_method_a, call_static_float_method_a,
+        get_float_field, set_float_field, get_static_float_field, set_static_float_field;
+    Jdouble => call_double_method_a, call_nonvirtual_double_method_a, call_static_double_method_a,
+        get_double_field, set_double_field, get_static_double_field, set_static_double_field
+}
+
+/// Creation of and access to primitive arrays.
+macro_rules! primitive_arrays {
+    ($($ty:ty, $array:ident => $new:ident ($new_name:expr), $get_region:ident, $set_region:ident,
+        $get_elements:ident ($get_elements_name:expr), $release_elements:ident);*) => {
+        impl<'local> Env<'local> {
+            $(
+                pub fn $new(&self, len:Jsize) -> Result<$array<'local>, JniError> {
+                    let array = jni_call!(self, $new, len);
+
+                    self.check_reference(array, $new_name)
+                }
+
+                /// Copies `buf.len()` elements starting at `start` into `buf`.
+                pub fn $get_region(&self, array:&$array, start:Jsize, buf:&mut [$ty]) -> Result<(), JniError> {
+                    jni_call!(self, $get_region, array.as_raw(), start, to_jsize(buf.len())?, buf.as_mut_ptr());
+
+                    self.check_exception()
+                }
+
+                /// Copies `buf` into the array, starting at `start`.
+                pub fn $set_region(&self, array:&$array, start:Jsize, buf:&[$ty]) -> Result<(), JniError> {
+                    jni_call!(self, $set_region, array.as_raw(), start, to_jsize(buf.len())?, buf.as_ptr());
+
+                    self.check_exception()
+                }
+
+                /// Borrows the elements of the array, which may be a copy.
+                pub fn $get_elements<'a>(&'a self, array:&'a $array) -> Result<ArrayElements<'a, 'local, $ty>, JniError> {
+                    let len = self.get_array_length(array)?;
+                    let elems = jni_call!(self, $get_elements, array.as_raw(), ptr::null_mut());
+
+                    self.check_exception()?;
+
+                    if elems.is_null() {
+                        return Err(JniError::NullResult($get_elements_name));
+                    }
+
+                    let release = unsafe { (*self.raw.as_ref().functions).$release_elements };
+
+                    Ok(unsafe { ArrayElements::new(self, array.as_raw(), elems, len as usize, release) })
+                }
+            )*
+        }
+    }
+}
+
+primitive_arrays! {
+    Jboolean, JBooleanArray => new_boolean_array("NewBooleanArray"), get_boolean_array_region, set_boolean_array_region,
+        get_boolean_array_elements("GetBooleanArrayElements"), release_boolean_array_elements;
+    Jbyte, JByteArray => new_byte_array("NewByteArray"), get_byte_array_region, set_byte_array_region,
+        get_byte_array_elements("GetByteArrayElements"), release_byte_array_elements;
+    Jchar, JCharArray => new_char_array("NewCharArray"), get_char_array_region, set_char_array_region,
+        get_char_array_elements("GetCharArrayElements"), release_char_array_elements;
+    Jshort, JShortArray => new_short_array("NewShortArray"), get_short_array_region, set_short_array_region,
+        get_short_array_elements("GetShortArrayElements"), release_short_array_elements;
+    Jint, JIntArray => new_int_array("NewIntArray"), get_int_array_region, set_int_array_region,
+        get_int_array_elements("GetIntArrayElements"), release_int_array_elements;
+    Jlong, JLongArray => new_long_array("NewLongArray"), get_long_array_region, set_long_array_region,
+        get_long_array_elements("GetLongArrayElements"), release_long_array_elements;
+    Jfloat, JFloatArray => new_float_array("NewFloatArray"), get_float_array_region, set_float_array_region,
+        get_float_array_elements("GetFloatArrayElements"), release_float_array_elements;
+    Jdouble, JDoubleArray => new_double_array("NewDoubleArray"), get_double_array_region, set_double_array_region,
+        get_double_array_elements("GetDoubleArrayElements"), release_double_array_elements
+}
@@ -0,0 +1,132 @@
+//! JNI errors
+
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use consts::*;
+use discovery::DiscoveryReport;
+use exception::JavaException;
+use types::*;
+
+/// Errors returned by this crate.
+#[derive(Debug)]
+pub enum JniError {
+    /// The JVM library could not be loaded.
+    LibraryLoad { path: PathBuf, message: String },
+    /// No JVM library was found by `JNI::discover()`.
+    LibraryNotFound(DiscoveryReport),
+    /// A function could not be found in the JVM library.
+    SymbolNotFound { name: String, message: String },
+    /// No Java VM has been created or attached to yet.
+    NoJavaVm,
+    /// `JNI_ERR`: unknown error.
+    Unknown,
+    /// `JNI_EDETACHED`: the current thread is not attached to the VM.
+    Detached,
+    /// `JNI_EVERSION`: the requested JNI version is not supported.
+    UnsupportedVersion(Jint),
+    /// `JNI_ENOMEM`: the VM ran out of memory.
+    OutOfMemory,
+    /// `JNI_EEXIST`: a Java VM has already been created in this process.
+    AlreadyExists,
+    /// `JNI_EINVAL`: the VM rejected its arguments.
+    InvalidArguments,
+    /// Any other negative JNI return code.
+    Other(Jint),
+    /// `DestroyJavaVM` did not return in time; the VM has been leaked.
+    ShutdownTimeout(Duration),
+    /// A Java exception was thrown, and has been cleared.
+    JavaException(Box<JavaException>),
+    /// A Java exception was thrown while collecting the details of another
+    /// one. Both have been cleared.
+    NestedException,
+    /// A Java exception is pending, which only allows exception-safe JNI
+    /// functions to be called.
+    ExceptionPending,
+    /// Another `Env` of the current thread is in use.
+    EnvInUse,
+    /// A JNI function unexpectedly returned null.
+    NullResult(&'static str),
+    /// The VM refused to reserve the given number of local references.
+    LocalCapacity(Jint),
+    /// An object is not an instance of the class it was cast to.
+    WrongType(&'static str),
+    /// An argument could not be passed to the VM, e.g. a string with a NUL byte.
+    InvalidArgument(String)
+}
+
+impl JniError {
+    /// Maps a JNI return code other than `JNI_OK` to an error.
+    ///
+    /// `JNI_EVERSION` only becomes `UnsupportedVersion` through
+    /// `check_version_result()`, which knows the requested version.
+    pub fn from_code(result:Jint) -> JniError {
+        match result {
+            JNI_ERR => JniError::Unknown,
+            JNI_EDETACHED => JniError::Detached,
+            JNI_ENOMEM => JniError::OutOfMemory,
+            JNI_EEXIST => JniError::AlreadyExists,
+            JNI_EINVAL => JniError::InvalidArguments,
+            _ => JniError::Other(result)
+        }
+    }
+}
+
+/// Converts a JNI return code into a `Result`.
+pub fn check_result(result:Jint) -> Result<(), JniError> {
+    match result {
+        JNI_OK => Ok(()),
+        _ => Err(JniError::from_code(result))
+    }
+}
+
+/// Converts the return code of a function which was asked for `version` into
+/// a `Result`.
+pub fn check_version_result(result:Jint, version:Jint) -> Result<(), JniError> {
+    match result {
+        JNI_EVERSION => Err(JniError::UnsupportedVersion(version)),
+        _ => check_result(result)
+    }
+}
+
+impl fmt::Display for JniError {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JniError::LibraryLoad { ref path, ref message } =>
+                write!(f, "Could not load JVM library '{}': {}", path.display(), message),
+            JniError::LibraryNotFound(ref report) => write!(f, "{}", report),
+            JniError::SymbolNotFound { ref name, ref message } =>
+                write!(f, "Could not find symbol {}: {}", name, message),
+            JniError::NoJavaVm => f.write_str("No Java VM has been created"),
+            JniError::Unknown => f.write_str("Unknown JNI error (JNI_ERR)"),
+            JniError::Detached => f.write_str("Current thread is not attached to the Java VM (JNI_EDETACHED)"),
+            JniError::UnsupportedVersion(version) => write!(f, "JNI version {:#x} is not supported (JNI_EVERSION)", version),
+            JniError::OutOfMemory => f.write_str("Java VM is out of memory (JNI_ENOMEM)"),
+            JniError::AlreadyExists => f.write_str("Java VM already exists (JNI_EEXIST)"),
+            JniError::InvalidArguments => f.write_str("Invalid arguments (JNI_EINVAL)"),
+            JniError::Other(result) => write!(f, "JNI error code {}", result),
+            JniError::ShutdownTimeout(timeout) => write!(f, "Java VM did not shut down within {:?}", timeout),
+            JniError::JavaException(ref exception) => write!(f, "Java exception thrown: {}", exception),
+            JniError::NestedException => f.write_str("Java exception thrown while describing another exception"),
+            JniError::ExceptionPending => f.write_str("Java exception pending"),
+            JniError::EnvInUse => f.write_str("Another Env of the current thread is in use"),
+            JniError::NullResult(name) => write!(f, "{} returned null", name),
+            JniError::LocalCapacity(capacity) => write!(f, "Could not reserve {} local references", capacity),
+            JniError::WrongType(class_name) => write!(f, "Object is not an instance of {}", class_name),
+            JniError::InvalidArgument(ref message) => write!(f, "Invalid argument: {}", message)
+        }
+    }
+}
+
+/// The source of a `JniError::JavaException` is the source of the exception,
+/// which is already part of the message.
+impl Error for JniError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            JniError::JavaException(ref exception) => exception.source(),
+            _ => None
+        }
+    }
+}
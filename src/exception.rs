@@ -0,0 +1,482 @@
+//! Java exception details
+
+use std::cell::Cell;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::sync::OnceLock;
+
+use attach::AttachArgs;
+use env::Env;
+use error::JniError;
+use objects::*;
+use refs::GlobalRef;
+
+thread_local! {
+    static DESCRIBING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Marks the current thread as describing an exception until it is dropped,
+/// also when a panic unwinds through the description.
+struct Describing;
+
+impl Describing {
+    /// Returns `None` if the thread is describing an exception already.
+    fn enter() -> Option<Describing> {
+        match DESCRIBING.with(|describing| describing.replace(true)) {
+            true => None,
+            false => Some(Describing)
+        }
+    }
+}
+
+impl Drop for Describing {
+    fn drop(&mut self) {
+        DESCRIBING.with(|describing| describing.set(false));
+    }
+}
+
+/// A Java exception which has been caught and cleared.
+///
+/// The throwable is kept alive, so that it can be thrown again. Its class name,
+/// message and printed stack trace are read when it is caught, so that they
+/// remain available after the VM is gone. The frames, cause and suppressed
+/// exceptions are collected when one of them is first used, on whichever thread
+/// uses them, and are empty if that fails. Exceptions which already appear
+/// further up the same chain are left out.
+#[derive(Clone, Debug)]
+pub struct JavaException {
+    /// The exception object.
+    pub throwable: GlobalRef,
+    /// Binary name of the exception class, e.g. `java.io.IOException`.
+    pub binary_name: String,
+    /// Result of `Throwable.getMessage()`.
+    pub message: Option<String>,
+    /// The class name as passed to `FindClass`, e.g. `java/io/IOException`.
+    jni_class_name: String,
+    stack_trace: String,
+    /// The exceptions this one is the cause or a suppressed exception of.
+    enclosing: Vec<GlobalRef>,
+    details: OnceLock<Details>
+}
+
+#[derive(Clone, Debug)]
+struct Details {
+    frames: Vec<StackFrame>,
+    cause: Option<Box<JavaException>>,
+    suppressed: Vec<JavaException>
+}
+
+/// Returned while the details cannot be collected, e.g. because the VM is gone.
+static NO_DETAILS:Details = Details {
+    frames: Vec::new(),
+    cause: None,
+    suppressed: Vec::new()
+};
+
+/// An element of a Java stack trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StackFrame {
+    pub class_name: String,
+    pub method_name: String,
+    pub file_name: Option<String>,
+    /// `None` if the line is unknown.
+    pub line_number: Option<u32>,
+    pub is_native: bool
+}
+
+impl fmt::Display for JavaException {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        match self.message {
+            Some(ref message) => write!(f, "{}: {}", self.binary_name, message),
+            None => f.write_str(&self.binary_name)
+        }
+    }
+}
+
+/// The source is the cause of the exception, or its first suppressed
+/// exception if there is no cause. `source()` can only return one error, so
+/// the other suppressed exceptions are only reachable through `suppressed()`.
+impl Error for JavaException {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.caused_by() {
+            Some(cause) => Some(cause),
+            None => self.suppressed().first().map(|suppressed| suppressed as &(dyn Error + 'static))
+        }
+    }
+}
+
+/// Formats the frame like `printStackTrace()`, without the leading "at".
+impl fmt::Display for StackFrame {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}(", self.class_name, self.method_name)?;
+
+        match (self.is_native, &self.file_name, self.line_number) {
+            (true, _, _) => f.write_str("Native Method")?,
+            (false, Some(file_name), Some(line_number)) => write!(f, "{}:{}", file_name, line_number)?,
+            (false, Some(file_name), None) => f.write_str(file_name)?,
+            (false, None, _) => f.write_str("Unknown Source")?
+        }
+
+        f.write_str(")")
+    }
+}
+
+impl JavaException {
+    /// Captures a throwable, which must not be pending.
+    ///
+    /// Details which cannot be retrieved, e.g. because `getMessage()` throws
+    /// itself, are left empty.
+    pub fn new(env:&Env, throwable:&JThrowable) -> Result<JavaException, JniError> {
+        capture(env, throwable, Vec::new())
+    }
+
+    /// Throws the exception again, leaving it pending.
+    pub fn throw(&self, env:&Env) -> Result<(), JniError> {
+        env.throw(self.as_throwable())
+    }
+
+    /// The stack trace as printed by `Throwable.printStackTrace()`, including
+    /// causes and suppressed exceptions.
+    pub fn stack_trace(&self) -> &str {
+        &self.stack_trace
+    }
+
+    /// Result of `Throwable.getStackTrace()`, innermost frame first.
+    pub fn frames(&self) -> &[StackFrame] {
+        &self.details().frames
+    }
+
+    /// Result of `Throwable.getCause()`. Not named `cause()`, which would be
+    /// shadowed by the deprecated `Error::cause()` when called on a `Box`.
+    pub fn caused_by(&self) -> Option<&JavaException> {
+        self.details().cause.as_deref()
+    }
+
+    /// Result of `Throwable.getSuppressed()`. Unlike `Error::source()`, this
+    /// includes all of them.
+    pub fn suppressed(&self) -> &[JavaException] {
+        &self.details().suppressed
+    }
+
+    fn as_throwable(&self) -> &JThrowable<'_> {
+        unsafe { JThrowable::from_object_ref_unchecked(self.throwable.as_obj()) }
+    }
+
+    /// Collects the details on first use. A failure is not remembered, as it
+    /// may be temporary, e.g. while another exception is pending.
+    fn details(&self) -> &Details {
+        if let Some(details) = self.details.get() {
+            return details;
+        }
+
+        match self.collect_details() {
+            Ok(details) => self.details.get_or_init(|| details),
+            Err(err) => {
+                warn!("Could not describe {}: {}", self, err);
+                &NO_DETAILS
+            }
+        }
+    }
+
+    fn collect_details(&self) -> Result<Details, JniError> {
+        // Reuses the attachment of the current thread if there is one. Another
+        // `Env` of the thread may be alive, but all local references are made
+        // in the frame below.
+        let attachment = self.throwable.vm().attach_current_thread(AttachArgs::default())?;
+        let env = unsafe { attachment.env_unchecked() };
+
+        let mut enclosing = self.enclosing.clone();
+        enclosing.push(self.throwable.clone());
+
+        let throwable = self.as_throwable();
+
+        // The details are collected in their own frame, so that the local
+        // references made on the way are freed.
+        env.with_frame(16, |env| {
+            Ok(Details {
+                frames: stack_frames(env, throwable).unwrap_or_default(),
+                cause: cause(env, throwable, &enclosing).unwrap_or(None).map(Box::new),
+                suppressed: suppressed(env, throwable, &enclosing).unwrap_or_default()
+            })
+        })
+    }
+}
+
+fn capture(env:&Env, throwable:&JThrowable, enclosing:Vec<GlobalRef>) -> Result<JavaException, JniError> {
+    let global = GlobalRef::new(env, throwable)?;
+
+    let (binary_name, message, stack_trace) = env.with_frame(16, |env| {
+        Ok((class_name(env, throwable).ok(), message(env, throwable).unwrap_or(None),
+            stack_trace(env, throwable).unwrap_or_default()))
+    })?;
+
+    let binary_name = binary_name.unwrap_or_else(|| String::from("<unknown>"));
+
+    Ok(JavaException {
+        throwable: global,
+        jni_class_name: binary_name.replace('.', "/"),
+        binary_name,
+        message,
+        stack_trace,
+        enclosing,
+        details: OnceLock::new()
+    })
+}
+
+/// Captures a cause or suppressed exception, unless it encloses itself, which
+/// `printStackTrace()` prints as a circular reference.
+fn capture_related(env:&Env, throwable:&JThrowable, enclosing:&[GlobalRef]) -> Result<Option<JavaException>, JniError> {
+    for other in enclosing {
+        if env.is_same_object(Some(other.as_obj()), Some(throwable))? {
+            return Ok(None);
+        }
+    }
+
+    capture(env, throwable, enclosing.to_vec()).map(Some)
+}
+
+fn to_string(env:&Env, string:Option<JObject>) -> Result<Option<String>, JniError> {
+    match string {
+        Some(string) => Ok(Some(env.get_string(&env.downcast(string)?)?)),
+        None => Ok(None)
+    }
+}
+
+fn class_name(env:&Env, throwable:&JThrowable) -> Result<String, JniError> {
+    let class = env.get_object_class(throwable)?;
+    let class_class = env.find_class("java/lang/Class")?;
+    let get_name = env.get_method_id(&class_class, "getName", "()Ljava/lang/String;")?;
+
+    to_string(env, env.call_object_method_a(&class, get_name, &[])?)?
+        .ok_or(JniError::NullResult("Class.getName()"))
+}
+
+fn message(env:&Env, throwable:&JThrowable) -> Result<Option<String>, JniError> {
+    let throwable_class = env.find_class("java/lang/Throwable")?;
+    let get_message = env.get_method_id(&throwable_class, "getMessage", "()Ljava/lang/String;")?;
+
+    to_string(env, env.call_object_method_a(throwable, get_message, &[])?)
+}
+
+fn stack_trace(env:&Env, throwable:&JThrowable) -> Result<String, JniError> {
+    // StringWriter writer = new StringWriter();
+    // throwable.printStackTrace(new PrintWriter(writer));
+
+    let string_writer_class = env.find_class("java/io/StringWriter")?;
+    let string_writer_ctor = env.get_method_id(&string_writer_class, "<init>", "()V")?;
+    let string_writer = env.new_object_a(&string_writer_class, string_writer_ctor, &[])?;
+
+    let print_writer_class = env.find_class("java/io/PrintWriter")?;
+    let print_writer_ctor = env.get_method_id(&print_writer_class, "<init>", "(Ljava/io/Writer;)V")?;
+    let print_writer = env.new_object_a(&print_writer_class, print_writer_ctor, &[(&string_writer).into()])?;
+
+    let throwable_class = env.find_class("java/lang/Throwable")?;
+    let print_stack_trace = env.get_method_id(&throwable_class, "printStackTrace", "(Ljava/io/PrintWriter;)V")?;
+    env.call_void_method_a(throwable, print_stack_trace, &[(&print_writer).into()])?;
+
+    let to_string_method = env.get_method_id(&string_writer_class, "toString", "()Ljava/lang/String;")?;
+
+    Ok(to_string(env, env.call_object_method_a(&string_writer, to_string_method, &[])?)?.unwrap_or_default())
+}
+
+fn stack_frames(env:&Env, throwable:&JThrowable) -> Result<Vec<StackFrame>, JniError> {
+    let throwable_class = env.find_class("java/lang/Throwable")?;
+    let get_stack_trace = env.get_method_id(&throwable_class, "getStackTrace", "()[Ljava/lang/StackTraceElement;")?;
+
+    let elements:JObjectArray = match env.call_object_method_a(throwable, get_stack_trace, &[])? {
+        Some(elements) => env.downcast(elements)?,
+        None => return Ok(Vec::new())
+    };
+
+    let element_class = env.find_class("java/lang/StackTraceElement")?;
+    let get_class_name = env.get_method_id(&element_class, "getClassName", "()Ljava/lang/String;")?;
+    let get_method_name = env.get_method_id(&element_class, "getMethodName", "()Ljava/lang/String;")?;
+    let get_file_name = env.get_method_id(&element_class, "getFileName", "()Ljava/lang/String;")?;
+    let get_line_number = env.get_method_id(&element_class, "getLineNumber", "()I")?;
+    let is_native_method = env.get_method_id(&element_class, "isNativeMethod", "()Z")?;
+
+    let len = env.get_array_length(&elements)?;
+    let mut frames = Vec::with_capacity(len as usize);
+
+    for index in 0..len {
+        // Stack traces can be long, so the references to each element are
+        // freed right away.
+        env.with_frame(8, |env| {
+            if let Some(element) = env.get_object_array_element(&elements, index)? {
+                frames.push(StackFrame {
+                    class_name: to_string(env, env.call_object_method_a(&element, get_class_name, &[])?)?.unwrap_or_default(),
+                    method_name: to_string(env, env.call_object_method_a(&element, get_method_name, &[])?)?.unwrap_or_default(),
+                    file_name: to_string(env, env.call_object_method_a(&element, get_file_name, &[])?)?,
+                    line_number: u32::try_from(env.call_int_method_a(&element, get_line_number, &[])?).ok(),
+                    is_native: env.call_boolean_method_a(&element, is_native_method, &[])?
+                });
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(frames)
+}
+
+fn cause(env:&Env, throwable:&JThrowable, enclosing:&[GlobalRef]) -> Result<Option<JavaException>, JniError> {
+    let throwable_class = env.find_class("java/lang/Throwable")?;
+    let get_cause = env.get_method_id(&throwable_class, "getCause", "()Ljava/lang/Throwable;")?;
+
+    match env.call_object_method_a(throwable, get_cause, &[])? {
+        Some(cause) => capture_related(env, &env.downcast(cause)?, enclosing),
+        None => Ok(None)
+    }
+}
+
+fn suppressed(env:&Env, throwable:&JThrowable, enclosing:&[GlobalRef]) -> Result<Vec<JavaException>, JniError> {
+    let throwable_class = env.find_class("java/lang/Throwable")?;
+    let get_suppressed = env.get_method_id(&throwable_class, "getSuppressed", "()[Ljava/lang/Throwable;")?;
+
+    let array:JObjectArray = match env.call_object_method_a(throwable, get_suppressed, &[])? {
+        Some(array) => env.downcast(array)?,
+        None => return Ok(Vec::new())
+    };
+
+    let mut suppressed = Vec::new();
+
+    for index in 0..env.get_array_length(&array)? {
+        // There may be many suppressed exceptions, so the references to each
+        // one are freed right away.
+        env.with_frame(4, |env| {
+            if let Some(element) = env.get_object_array_element(&array, index)? {
+                if let Some(exception) = capture_related(env, &env.downcast(element)?, enclosing)? {
+                    suppressed.push(exception);
+                }
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(suppressed)
+}
+
+/// Clears the pending Java exception, if any, and returns it.
+///
+/// This replaces `exception_describe()`, which prints to the standard error
+/// stream of the process.
+pub fn take_exception(env:&Env) -> Result<Option<JavaException>, JniError> {
+    let throwable = match env.exception_occurred() {
+        Some(throwable) => AutoLocal::new(env, throwable),
+        None => return Ok(None)
+    };
+
+    env.exception_clear();
+
+    // An exception thrown while capturing another one is dropped, instead of
+    // being captured in turn.
+    let _describing = match Describing::enter() {
+        Some(describing) => describing,
+        None => return Err(JniError::NestedException)
+    };
+
+    JavaException::new(env, &throwable).map(Some)
+}
+
+/// Conversion of Rust errors into Java exceptions, which are thrown by the
+/// native method boundary `native::catch_result()`.
+pub trait ToJavaException {
+    /// The exception class, e.g. `"java/io/IOException"`, which must have a
+    /// constructor taking a message string.
+    fn class_name(&self) -> &str {
+        "java/lang/RuntimeException"
+    }
+
+    fn message(&self) -> String;
+
+    /// The error set as the cause of the exception.
+    fn cause(&self) -> Option<&dyn ToJavaException> {
+        None
+    }
+
+    /// Creates the exception object. The default creates an instance of
+    /// `class_name()` with `message()`, and the exception of `cause()` as
+    /// its cause. The cause is dropped if the constructor sets one already,
+    /// as some do with `null`, since `Throwable.initCause()` refuses to
+    /// replace it.
+    fn to_throwable<'local>(&self, env:&Env<'local>) -> Result<JThrowable<'local>, JniError> {
+        new_throwable(env, self.class_name(), &self.message(), self.cause())
+    }
+}
+
+fn new_throwable<'local>(env:&Env<'local>, class_name:&str, message:&str, cause:Option<&dyn ToJavaException>) -> Result<JThrowable<'local>, JniError> {
+    let class = env.find_class(class_name)?;
+    let ctor = env.get_method_id(&class, "<init>", "(Ljava/lang/String;)V")?;
+    let message = env.new_string(message)?;
+    let throwable = env.new_object_a(&class, ctor, &[(&message).into()])?;
+
+    if let Some(cause) = cause {
+        // Not every exception class has a constructor taking a cause.
+        let cause = cause.to_throwable(env)?;
+        let throwable_class = env.find_class("java/lang/Throwable")?;
+        let init_cause = env.get_method_id(&throwable_class, "initCause", "(Ljava/lang/Throwable;)Ljava/lang/Throwable;")?;
+
+        match env.call_object_method_a(&throwable, init_cause, &[(&cause).into()]) {
+            Ok(_) => {},
+            // Thrown if the constructor has set a cause already.
+            Err(JniError::JavaException(ref exception)) if exception.binary_name == "java.lang.IllegalStateException" => {
+                warn!("Dropping the cause of {}, which already has one", class_name);
+            },
+            Err(err) => return Err(err)
+        }
+    }
+
+    env.downcast(throwable)
+}
+
+impl ToJavaException for JavaException {
+    /// The class name in the form passed to `FindClass`, e.g.
+    /// `java/io/IOException`. The binary name is in the `binary_name` field.
+    fn class_name(&self) -> &str {
+        &self.jni_class_name
+    }
+
+    fn message(&self) -> String {
+        self.message.clone().unwrap_or_default()
+    }
+
+    fn to_throwable<'local>(&self, env:&Env<'local>) -> Result<JThrowable<'local>, JniError> {
+        env.downcast(self.throwable.to_local(env)?)
+    }
+}
+
+impl ToJavaException for JniError {
+    fn message(&self) -> String {
+        self.to_string()
+    }
+
+    /// A `JniError::JavaException` is thrown again.
+    fn to_throwable<'local>(&self, env:&Env<'local>) -> Result<JThrowable<'local>, JniError> {
+        match *self {
+            JniError::JavaException(ref exception) => exception.to_throwable(env),
+            _ => new_throwable(env, self.class_name(), &self.message(), None)
+        }
+    }
+}
+
+impl ToJavaException for io::Error {
+    fn class_name(&self) -> &str {
+        "java/io/IOException"
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Throws the exception for `error`, leaving it pending. If an exception is
+/// already pending, e.g. because `error` is `JniError::ExceptionPending`, it
+/// is kept instead.
+pub fn throw_exception(env:&Env, error:&dyn ToJavaException) -> Result<(), JniError> {
+    if env.exception_check() {
+        return Ok(());
+    }
+
+    env.throw(&error.to_throwable(env)?)
+}
@@ -0,0 +1,120 @@
+//! JVM message, exit and abort hooks
+//!
+//! The invocation API accepts the special options `vfprintf`, `exit` and
+//! `abort`, whose `extraInfo` is a function pointer called by the VM. These
+//! functions take no user data, so the installed closures are kept in
+//! process-wide slots; there can only be one Java VM per process anyway.
+//!
+//! The `vfprintf` hook receives a `va_list`, so it is implemented by a small C
+//! shim which formats the message and passes it on.
+
+use libc::{c_char, c_void};
+use std::ffi::CStr;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use types::*;
+
+pub type VfprintfHook = Box<dyn Fn(&str) + Send + Sync>;
+pub type ExitHook = Box<dyn Fn(Jint) + Send + Sync>;
+pub type AbortHook = Box<dyn Fn() + Send + Sync>;
+
+type SharedVfprintfHook = Arc<dyn Fn(&str) + Send + Sync>;
+type SharedExitHook = Arc<dyn Fn(Jint) + Send + Sync>;
+type SharedAbortHook = Arc<dyn Fn() + Send + Sync>;
+
+static VFPRINTF_HOOK:Mutex<Option<SharedVfprintfHook>> = Mutex::new(None);
+static EXIT_HOOK:Mutex<Option<SharedExitHook>> = Mutex::new(None);
+static ABORT_HOOK:Mutex<Option<SharedAbortHook>> = Mutex::new(None);
+
+extern "system" {
+    // Only passed to the VM, so the `va_list` parameter is left out here.
+    fn jni_hooks_vfprintf();
+}
+
+fn lock<T>(slot:&Mutex<Option<T>>) -> MutexGuard<'_, Option<T>> {
+    slot.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Returns the installed hook without holding the lock while it runs, so that
+/// a hook may block, or install hooks itself.
+fn current<T:Clone>(slot:&Mutex<Option<T>>) -> Option<T> {
+    lock(slot).clone()
+}
+
+/// Panics must not unwind into the VM. They are reported by the panic hook,
+/// and otherwise ignored.
+fn call<F:FnOnce()>(f:F) {
+    let _ = panic::catch_unwind(AssertUnwindSafe(f));
+}
+
+/// Called by the C shim with the formatted message.
+#[no_mangle]
+extern "C" fn jni_hooks_vfprintf_message(message:*const c_char) {
+    if let Some(hook) = current(&VFPRINTF_HOOK) {
+        let message = unsafe {
+            CStr::from_ptr(message).to_string_lossy()
+        };
+
+        call(|| hook(&message));
+    }
+}
+
+extern "system" fn exit_hook(code:Jint) {
+    if let Some(hook) = current(&EXIT_HOOK) {
+        call(|| hook(code));
+    }
+}
+
+extern "system" fn abort_hook() {
+    if let Some(hook) = current(&ABORT_HOOK) {
+        call(|| hook());
+    }
+}
+
+/// The hooks which were replaced while creating a Java VM.
+#[derive(Default)]
+pub struct ReplacedHooks {
+    vfprintf: Option<Option<SharedVfprintfHook>>,
+    exit: Option<Option<SharedExitHook>>,
+    abort: Option<Option<SharedAbortHook>>
+}
+
+impl ReplacedHooks {
+    /// Puts the replaced hooks back, after the VM could not be created.
+    pub fn restore(self) {
+        if let Some(hook) = self.vfprintf {
+            *lock(&VFPRINTF_HOOK) = hook;
+        }
+
+        if let Some(hook) = self.exit {
+            *lock(&EXIT_HOOK) = hook;
+        }
+
+        if let Some(hook) = self.abort {
+            *lock(&ABORT_HOOK) = hook;
+        }
+    }
+}
+
+/// Installs the `vfprintf` hook and returns the function pointer to pass as
+/// `extraInfo`.
+pub fn install_vfprintf_hook(hook:VfprintfHook, replaced:&mut ReplacedHooks) -> *mut c_void {
+    replaced.vfprintf = Some(lock(&VFPRINTF_HOOK).replace(Arc::from(hook)));
+    jni_hooks_vfprintf as *mut c_void
+}
+
+/// Installs the `exit` hook and returns the function pointer to pass as
+/// `extraInfo`.
+pub fn install_exit_hook(hook:ExitHook, replaced:&mut ReplacedHooks) -> *mut c_void {
+    replaced.exit = Some(lock(&EXIT_HOOK).replace(Arc::from(hook)));
+    exit_hook as *mut c_void
+}
+
+/// Installs the `abort` hook and returns the function pointer to pass as
+/// `extraInfo`.
+pub fn install_abort_hook(hook:AbortHook, replaced:&mut ReplacedHooks) -> *mut c_void {
+    replaced.abort = Some(lock(&ABORT_HOOK).replace(Arc::from(hook)));
+    abort_hook as *mut c_void
+}
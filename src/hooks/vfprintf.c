@@ -0,0 +1,35 @@
+/*
+ * The `vfprintf` hook of the invocation API receives a `va_list`, whose type
+ * depends on the platform ABI. This shim formats the message in C and hands
+ * the result to Rust.
+ */
+
+#include <stdarg.h>
+#include <stdio.h>
+
+#ifdef _WIN32
+#define JNICALL __stdcall
+#else
+#define JNICALL
+#endif
+
+/* Messages longer than this are truncated. */
+#define MESSAGE_BUFFER_SIZE 4096
+
+extern void jni_hooks_vfprintf_message(const char *message);
+
+int JNICALL jni_hooks_vfprintf(FILE *stream, const char *format, va_list args)
+{
+    char buf[MESSAGE_BUFFER_SIZE];
+    int len;
+
+    (void) stream;
+
+    len = vsnprintf(buf, sizeof(buf), format, args);
+
+    if (len >= 0) {
+        jni_hooks_vfprintf_message(buf);
+    }
+
+    return len;
+}
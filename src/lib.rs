@@ -1,140 +1,340 @@
 #![crate_name = "jni"]
 extern crate libc;
+#[macro_use]
+extern crate log;
 extern crate shared_library;
 
+pub mod attach;
+pub mod builder;
 pub mod classpath;
 pub mod consts;
+pub mod discovery;
+pub mod elements;
+pub mod env;
+pub mod error;
+pub mod exception;
+pub mod hooks;
+pub mod mutf8;
+pub mod native;
+pub mod objects;
+pub mod refs;
+pub mod shutdown;
+pub mod sys;
 pub mod ffi;
 pub mod types;
+pub mod value;
+pub mod version;
+pub mod vm;
 
-use libc::size_t;
-use libc::funcs::c95::stdlib::malloc;
+use libc::c_void;
 use shared_library::dynamic_library::DynamicLibrary;
 use std::mem::transmute;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::ptr;
+use std::sync::Arc;
 
+use attach::{AttachArgs, AttachGuard};
 use consts::*;
-use ffi::{JavaVMInitArgs, JavaVMOption, JavaVM, JNIEnv};
+use discovery::{Candidate, DiscoveryReport};
+use env::Env;
+use error::{check_result, check_version_result, JniError};
+use ffi::{JavaVMInitArgs, JavaVM, JNIEnv};
+use shutdown::{DropBehavior, ShutdownOptions};
+use sys::{JNICreateJavaVM, JNIGetCreatedJavaVMs, JNIGetDefaultJavaVMInitArgs};
 use types::*;
+use version::JniVersion;
+use vm::JavaVm;
+
+pub use builder::JvmBuilder;
 
 pub struct JNI {
-    libjvm:DynamicLibrary,
-    vm_init_args:Box<ffi::JavaVMInitArgs>,
-    vm_options:Vec<ffi::JavaVMOption>,
-    jvm:Box<*mut ffi::JavaVM>,
-    env:Box<*mut ffi::JNIEnv>
+    libjvm:Arc<DynamicLibrary>,
+    vm:Option<JavaVm>,
+    env:*mut JNIEnv,
+    owns_jvm:bool,
+    drop_behavior:DropBehavior,
+    attachment:Option<AttachGuard>
 }
 
 impl Drop for JNI {
     fn drop(&mut self) {
-        println!("Destroying JVM instance ...");
-        if !self.jvm.is_null() {
-            ffi::destroy_java_vm(*self.jvm);
+        let vm = match self.vm {
+            Some(ref vm) if self.owns_jvm => vm,
+            _ => return
+        };
+
+        let result = match self.drop_behavior {
+            DropBehavior::Destroy => {
+                debug!("Destroying JVM instance ...");
+                vm.set_destroyed();
+                check_result(unsafe { ffi::destroy_java_vm(&*vm.as_raw()) })
+            },
+            DropBehavior::Leak => {
+                debug!("Leaking JVM instance");
+                Ok(())
+            },
+            DropBehavior::Halt(status) => {
+                debug!("Halting JVM instance with status {}", status);
+                vm.attach_current_thread(AttachArgs::new())
+                    .and_then(|attachment| shutdown::call_runtime(unsafe { &attachment.env_unchecked() }, "halt", status))
+            }
+        };
+
+        if let Err(error) = result {
+            warn!("Error shutting down JVM instance: {}", error);
         }
-        ffi::free_jvm_options(&self.vm_options);
     }
 }
 
-type JNICreateJavaVM = extern "C" fn(*mut(*mut JavaVM), *mut(*mut JNIEnv), *const JavaVMInitArgs) -> Jint;
-
 impl JNI {
-    pub fn new(libjvm_path:&Path) -> Result<JNI, String> {
+    pub fn new(libjvm_path:&Path) -> Result<JNI, JniError> {
         let libjvm = match DynamicLibrary::open(Some(libjvm_path)) {
-            Err(error) => return Err(format!("Could not load JVM library '{}': {}", libjvm_path.display(), error)),
+            Err(message) => return Err(JniError::LibraryLoad { path: libjvm_path.to_path_buf(), message }),
             Ok(libjvm) => libjvm
         };
 
-        println!("JVM library loaded.");
+        debug!("JVM library loaded from '{}'", libjvm_path.display());
 
-        Ok(JNI {
-            libjvm: libjvm,
-            vm_init_args: Box::new(JavaVMInitArgs {
-                version: JNI_VERSION_1_6,
-                n_options: 0,
-                options: ptr::null_mut(),
-                ignore_unrecognized: JNI_FALSE
-            }),
-            vm_options: Vec::new(),
-            jvm: Box::new(ptr::null_mut()),
-            env: Box::new(ptr::null_mut())
-        })
+        Ok(JNI::with_library(libjvm))
     }
 
-    pub fn init_vm_args(&mut self, n_options:usize) {
-        let mut v:Vec<JavaVMOption> = Vec::with_capacity(n_options);
+    /// Uses the JVM library which is already loaded into the current process,
+    /// e.g. if this code runs as a native library inside a Java application.
+    pub fn current_process() -> Result<JNI, JniError> {
+        match DynamicLibrary::open(None) {
+            Err(message) => Err(JniError::LibraryLoad { path: PathBuf::new(), message }),
+            Ok(libjvm) => Ok(JNI::with_library(libjvm))
+        }
+    }
 
-        unsafe {
-            v.set_len(n_options);
+    fn with_library(libjvm:DynamicLibrary) -> JNI {
+        JNI {
+            libjvm: Arc::new(libjvm),
+            vm: None,
+            env: ptr::null_mut(),
+            owns_jvm: false,
+            drop_behavior: DropBehavior::Destroy,
+            attachment: None
         }
+    }
 
-        for i in 0..n_options {
-            match v.get_mut(i) {
-                Some(opt) => {
-                    opt.option_string = ptr::null();
-                    opt.extra_info = ptr::null_mut();
-                },
-                None => panic!("Out of bounds for VM arguments!")
-            }
+    /// Searches the usual Java installation locations for a JVM library and
+    /// loads the first one that can be opened.
+    ///
+    /// See `discovery::candidates()` for the locations searched.
+    pub fn discover() -> Result<JNI, JniError> {
+        let mut report = DiscoveryReport::default();
+
+        for (path, source) in discovery::candidates() {
+            trace!("Trying JVM library '{}' ({})", path.display(), source);
+
+            let error = if !path.is_file() {
+                "not found".to_string()
+            } else {
+                match JNI::new(&path) {
+                    Ok(jni) => return Ok(jni),
+                    Err(error) => error.to_string()
+                }
+            };
+
+            report.candidates.push(Candidate { path, source, error });
         }
 
-        self.vm_init_args.n_options = n_options as Jint;
-        self.vm_init_args.options = v.as_mut_ptr();
-        self.vm_options = v;
+        Err(JniError::LibraryNotFound(report))
     }
 
-    pub fn push_vm_arg(&mut self, index:usize, option:&str) {
-        let size = option.len() + 1;
+    fn symbol(&self, name:&str) -> Result<*mut u8, JniError> {
+        unsafe {
+            self.libjvm.symbol::<u8>(name)
+                .map_err(|message| JniError::SymbolNotFound { name: name.to_string(), message })
+        }
+    }
 
-        let unsafe_mem:*mut u8 = unsafe {
-            transmute::<_, *mut u8>(malloc(size as size_t))
+    fn create_java_vm(&mut self, vm_init_args:&JavaVMInitArgs, version:JniVersion) -> Result<(), JniError> {
+        let jni_create_java_vm = unsafe {
+            transmute::<*mut u8, JNICreateJavaVM>(self.symbol("JNI_CreateJavaVM")?)
         };
 
-        unsafe {
-            ptr::write_bytes(unsafe_mem, 0, size);
-            ptr::copy_nonoverlapping(option.as_ptr(), unsafe_mem, size - 1);
+        let mut jvm:*mut JavaVM = ptr::null_mut();
+        let result = unsafe {
+            jni_create_java_vm(&mut jvm, &mut self.env as *mut *mut JNIEnv as *mut *mut c_void,
+                vm_init_args as *const JavaVMInitArgs as *mut c_void)
+        };
 
-            let ref mut v:Vec<JavaVMOption> = self.vm_options;
+        check_version_result(result, vm_init_args.version)?;
 
-            match v.get_mut(index) {
-                Some(opt) => {
-                    opt.option_string = transmute::<_, *const i8>(unsafe_mem);
-                    opt.extra_info = ptr::null_mut();
-                },
-                None => panic!("Out of bounds for VM arguments!")
-            }
-        }
+        self.vm = Some(JavaVm::with_library(jvm, version, self.libjvm.clone()));
+        self.owns_jvm = true;
+
+        Ok(())
     }
 
-    pub fn create_java_vm(&mut self) -> Result<&mut JNI, String> {
-        let jni_create_java_vm:JNICreateJavaVM = unsafe {
-            match self.libjvm.symbol("JNI_CreateJavaVM") {
-                Err(error) => return Err(format!("Could not find symbol JNI_CreateJavaVM: {}", error)),
-                Ok(jni_create_java_vm) => transmute::<*mut u8, _>(jni_create_java_vm)
-            }
+    /// Returns the default initialization arguments the JVM library reports for
+    /// the requested JNI version. Fails if the version is not supported.
+    ///
+    /// JNI 1.1 is always rejected, as the VM expects a `JDK1_1InitArgs`
+    /// structure for it, which is larger than `JavaVMInitArgs`.
+    pub fn get_default_java_vm_init_args(&self, version:JniVersion) -> Result<JavaVMInitArgs, JniError> {
+        if version == JniVersion::V1_1 {
+            return Err(JniError::UnsupportedVersion(version.to_jint()));
+        }
+
+        let jni_get_default_java_vm_init_args = unsafe {
+            transmute::<*mut u8, JNIGetDefaultJavaVMInitArgs>(self.symbol("JNI_GetDefaultJavaVMInitArgs")?)
         };
 
-        {
-            let args:&JavaVMInitArgs = &(*self.vm_init_args);
-            let result = jni_create_java_vm(&mut *self.jvm, &mut *self.env, args);
+        let mut vm_init_args = JavaVMInitArgs {
+            version: version.to_jint(),
+            n_options: 0,
+            options: ptr::null_mut(),
+            ignore_unrecognized: JNI_FALSE
+        };
 
-            if result != JNI_OK {
-                return Err(format!("Error calling JNI_CreateJavaVM: error code {:x}", result));
+        let result = unsafe {
+            jni_get_default_java_vm_init_args(&mut vm_init_args as *mut JavaVMInitArgs as *mut c_void)
+        };
+
+        // HotSpot reports versions it does not know with `JNI_ERR`.
+        match result {
+            JNI_ERR => return Err(JniError::UnsupportedVersion(version.to_jint())),
+            _ => check_version_result(result, version.to_jint())?
+        }
+
+        Ok(vm_init_args)
+    }
+
+    /// Returns the newest JNI version the JVM library accepts for creating a
+    /// Java VM, by querying `JNI_GetDefaultJavaVMInitArgs` for each version.
+    pub fn get_supported_version(&self) -> Result<JniVersion, JniError> {
+        for version in JniVersion::all().iter().rev() {
+            if self.get_default_java_vm_init_args(*version).is_ok() {
+                return Ok(*version);
             }
         }
 
-        Ok(self)
+        // Not even the oldest version is supported.
+        Err(JniError::UnsupportedVersion(JniVersion::all()[0].to_jint()))
     }
 
-    pub fn get_jvm(&mut self) -> &mut JavaVM {
-        unsafe {
-            &mut (*(*self.jvm))
+    /// Returns all Java VMs which have been created in the current process.
+    pub fn get_created_java_vms(&self) -> Result<Vec<*mut JavaVM>, JniError> {
+        let jni_get_created_java_vms = unsafe {
+            transmute::<*mut u8, JNIGetCreatedJavaVMs>(self.symbol("JNI_GetCreatedJavaVMs")?)
+        };
+
+        let mut n_vms:Jsize = 0;
+        let result = unsafe {
+            jni_get_created_java_vms(ptr::null_mut(), 0, &mut n_vms)
+        };
+
+        check_result(result)?;
+
+        let mut vms:Vec<*mut JavaVM> = vec![ptr::null_mut(); n_vms as usize];
+        let result = unsafe {
+            jni_get_created_java_vms(vms.as_mut_ptr(), n_vms, &mut n_vms)
+        };
+
+        check_result(result)?;
+
+        vms.truncate(n_vms as usize);
+
+        Ok(vms)
+    }
+
+    /// Attaches the current thread to a Java VM which already exists in this
+    /// process, instead of creating a new one. The VM is not destroyed when
+    /// this instance is dropped.
+    pub fn attach_created_java_vm(&mut self) -> Result<(), JniError> {
+        let jvm = match self.get_created_java_vms()?.first() {
+            Some(jvm) => *jvm,
+            None => return Err(JniError::NoJavaVm)
+        };
+
+        // The version is only known to an attached thread. The handle which
+        // attaches for it is dropped, so that the one made here keeps the
+        // library loaded, unless there is another handle to the VM already.
+        let version = {
+            let attachment = unsafe { AttachGuard::new(jvm, &AttachArgs::new())? };
+            unsafe { Env::from_raw(attachment.as_raw()) }.get_version()?
+        };
+
+        let vm = JavaVm::with_library(jvm, version, self.libjvm.clone());
+        let attachment = vm.attach_current_thread(AttachArgs::new())?;
+
+        self.env = attachment.as_raw();
+        self.vm = Some(vm);
+        self.owns_jvm = false;
+        self.attachment = Some(attachment);
+
+        Ok(())
+    }
+
+    /// Attaches the current thread to the Java VM. The returned guard detaches
+    /// the thread again when dropped, if it wasn't attached before.
+    pub fn attach_current_thread(&self, args:AttachArgs) -> Result<AttachGuard, JniError> {
+        self.vm()?.attach_current_thread(args)
+    }
+
+    /// Returns the `JNIEnv` of the current thread, or `JniError::Detached` if
+    /// the thread is not attached to the Java VM.
+    ///
+    /// Fails with `JniError::EnvInUse` while another `Env` of the current
+    /// thread is alive, e.g. one of an `AttachGuard`.
+    pub fn env(&mut self) -> Result<Env<'_>, JniError> {
+        unsafe { self.vm()?.env() }.and_then(Env::claim)
+    }
+
+    /// Returns the `JNIEnv` of the current thread, attaching the thread until
+    /// it exits if needed. See `attach::get_env_or_attach()`.
+    ///
+    /// Fails with `JniError::EnvInUse` like `env()`.
+    pub fn env_or_attach(&mut self, args:AttachArgs) -> Result<Env<'_>, JniError> {
+        unsafe { self.vm()?.env_or_attach(args) }.and_then(Env::claim)
+    }
+
+    fn vm(&self) -> Result<&JavaVm, JniError> {
+        self.vm.as_ref().ok_or(JniError::NoJavaVm)
+    }
+
+    /// Returns a handle to the Java VM, which can be shared with other threads.
+    pub fn java_vm(&self) -> Result<JavaVm, JniError> {
+        self.vm().cloned()
+    }
+
+    /// Returns the JNI version supported by the running Java VM.
+    pub fn get_version(&self) -> Result<JniVersion, JniError> {
+        if self.env.is_null() {
+            return Err(JniError::NoJavaVm);
         }
+
+        unsafe { Env::from_raw(self.env) }.get_version()
     }
 
-    pub fn get_env(&mut self) -> &mut JNIEnv {
-        unsafe {
-            &mut (*(*self.env))
+    /// Sets what happens to the Java VM when this instance is dropped.
+    /// Has no effect if the VM was not created by this instance.
+    pub fn set_drop_behavior(&mut self, drop_behavior:DropBehavior) {
+        self.drop_behavior = drop_behavior;
+    }
+
+    /// Shuts down the Java VM created by this instance, and reports the result.
+    ///
+    /// Without a timeout, this blocks until all non-daemon Java threads have
+    /// finished. If the VM was not created by this instance, the current thread
+    /// is detached from it instead.
+    ///
+    /// The VM is not used afterwards, even if this fails. See
+    /// `shutdown::shutdown()` for what happens if `Runtime` cannot be called.
+    pub fn shutdown(mut self, options:ShutdownOptions) -> Result<(), JniError> {
+        // the environment of the calling thread may not be used afterwards
+        self.attachment = None;
+
+        if !self.owns_jvm {
+            return Ok(());
         }
+
+        let vm = self.vm.take().ok_or(JniError::NoJavaVm)?;
+        self.env = ptr::null_mut();
+
+        unsafe { shutdown::shutdown(&vm, &options) }
     }
 }
@@ -0,0 +1,70 @@
+//! Modified UTF-8, the string encoding used by JNI
+//!
+//! It differs from standard UTF-8 in two ways: NUL is encoded as the two
+//! bytes `C0 80`, so that encoded strings never contain a zero byte, and
+//! supplementary characters are encoded as a surrogate pair of three bytes
+//! each.
+
+use std::ffi::CString;
+
+/// Encodes a string as modified UTF-8.
+pub fn to_modified_utf8(value:&str) -> CString {
+    let mut bytes = Vec::with_capacity(value.len() + 1);
+
+    for c in value.chars() {
+        match c as u32 {
+            0 => bytes.extend_from_slice(&[0xc0, 0x80]),
+            0x1_0000..=0x10_ffff => {
+                let mut units = [0; 2];
+
+                for unit in c.encode_utf16(&mut units).iter() {
+                    push_three_bytes(&mut bytes, *unit);
+                }
+            },
+            _ => {
+                let mut buf = [0; 4];
+                bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            }
+        }
+    }
+
+    // No zero bytes are left.
+    unsafe { CString::from_vec_unchecked(bytes) }
+}
+
+fn push_three_bytes(bytes:&mut Vec<u8>, unit:u16) {
+    bytes.push(0xe0 | (unit >> 12) as u8);
+    bytes.push(0x80 | ((unit >> 6) & 0x3f) as u8);
+    bytes.push(0x80 | (unit & 0x3f) as u8);
+}
+
+/// Decodes modified UTF-8, e.g. from `Env::get_string_utf_chars()`. Invalid
+/// sequences are replaced with U+FFFD.
+pub fn from_modified_utf8(bytes:&[u8]) -> String {
+    let mut units:Vec<u16> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+
+        let (unit, len) = match b {
+            0x01..=0x7f => (u16::from(b), 1),
+            0xc0..=0xdf if continues(bytes, i + 1, 1) =>
+                ((u16::from(b & 0x1f) << 6) | u16::from(bytes[i + 1] & 0x3f), 2),
+            0xe0..=0xef if continues(bytes, i + 1, 2) =>
+                ((u16::from(b & 0x0f) << 12) | (u16::from(bytes[i + 1] & 0x3f) << 6) | u16::from(bytes[i + 2] & 0x3f), 3),
+            _ => (0xfffd, 1)
+        };
+
+        units.push(unit);
+        i += len;
+    }
+
+    // Surrogate pairs are joined, unpaired surrogates replaced.
+    String::from_utf16_lossy(&units)
+}
+
+/// Checks for `n` continuation bytes starting at `start`.
+fn continues(bytes:&[u8], start:usize, n:usize) -> bool {
+    bytes.len() >= start + n && bytes[start..start + n].iter().all(|b| b & 0xc0 == 0x80)
+}
@@ -0,0 +1,206 @@
+//! Native method boundaries
+//!
+//! A panic unwinding out of a native method into the Java VM aborts the
+//! process. `catch_panic()` runs the body of a native method and turns a panic
+//! into a Java exception instead, returning a default value to the VM.
+//! `catch_result()` does the same, and also throws the exception chosen by
+//! `ToJavaException` when the body returns an error.
+
+use std::any::Any;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::{Cell, RefCell};
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::ptr;
+use std::sync::Once;
+
+use env::Env;
+use exception::{throw_exception, ToJavaException};
+use ffi::JNIEnv;
+use types::*;
+
+/// The class thrown for panics by `catch_panic()`.
+pub const PANIC_EXCEPTION_CLASS:&str = "java/lang/RuntimeException";
+
+thread_local! {
+    // The number of native method boundaries the current thread is in.
+    static BOUNDARY_DEPTH: Cell<usize> = const { Cell::new(0) };
+    // Location and backtrace of the last panic inside a boundary.
+    static PANIC_DETAILS: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_PANIC_HOOK:Once = Once::new();
+
+/// Return types of native methods, with the value returned when the method
+/// throws. The VM ignores the value in that case.
+pub trait NativeReturn {
+    fn default_value() -> Self;
+}
+
+impl NativeReturn for () {
+    fn default_value() {}
+}
+
+impl NativeReturn for Jobject {
+    fn default_value() -> Jobject {
+        ptr::null_mut()
+    }
+}
+
+macro_rules! zero_native_returns {
+    ($($ty:ty),*) => {
+        $(
+            impl NativeReturn for $ty {
+                fn default_value() -> $ty {
+                    0 as $ty
+                }
+            }
+        )*
+    }
+}
+
+zero_native_returns!(Jboolean, Jbyte, Jchar, Jshort, Jint, Jlong, Jfloat, Jdouble);
+
+/// Chains a panic hook which records the location and backtrace of panics
+/// inside a boundary, instead of printing them. Panics elsewhere are passed
+/// on to the previous hook.
+///
+/// If another hook is set later on, thrown exceptions only carry the panic
+/// message.
+fn install_panic_hook() {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if BOUNDARY_DEPTH.try_with(|depth| depth.get()).unwrap_or(0) == 0 {
+                return previous(info);
+            }
+
+            let location = match info.location() {
+                Some(location) => location.to_string(),
+                None => String::from("<unknown>")
+            };
+            // Captured only if enabled by `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE`.
+            let backtrace = Backtrace::capture();
+            let details = match backtrace.status() {
+                BacktraceStatus::Captured => format!("at {}\n\nRust backtrace:\n{}", location, backtrace),
+                _ => format!("at {}", location)
+            };
+
+            let _ = PANIC_DETAILS.try_with(|panic_details| *panic_details.borrow_mut() = Some(details));
+        }));
+    });
+}
+
+fn panic_message(payload:&(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+fn throw_panic(env:&Env, class_name:&str, payload:&(dyn Any + Send)) {
+    let mut message = format!("Rust panic: {}", panic_message(payload));
+
+    if let Some(details) = PANIC_DETAILS.with(|panic_details| panic_details.borrow_mut().take()) {
+        message.push('\n');
+        message.push_str(&details);
+    }
+
+    if env.exception_check() {
+        error!("Keeping pending Java exception over {}", message);
+        return;
+    }
+
+    let result = env.throw_new(class_name, &message).or_else(|err| {
+        warn!("Could not throw {}: {}", class_name, err);
+        env.throw_new(PANIC_EXCEPTION_CLASS, &message)
+    });
+
+    if let Err(err) = result {
+        error!("Could not throw exception for {}: {}", message, err);
+    }
+}
+
+fn throw_error(env:&Env, error:&dyn ToJavaException) {
+    let result = throw_exception(env, error).or_else(|err| {
+        warn!("Could not throw {}: {}", error.class_name(), err);
+        env.throw_new("java/lang/RuntimeException", &error.message())
+    });
+
+    if let Err(err) = result {
+        error!("Could not throw exception for {}: {}", error.message(), err);
+    }
+}
+
+/// Runs the body of a native method, turning a panic into a
+/// `java.lang.RuntimeException`. See `catch_panic_as()`.
+///
+/// # Safety
+///
+/// `env` must be the `JNIEnv` passed to the native method.
+pub unsafe fn catch_panic<T, F>(env:*mut JNIEnv, f:F) -> T
+    where T: NativeReturn, F: for<'local> FnOnce(&mut Env<'local>) -> T
+{
+    catch_panic_as(env, PANIC_EXCEPTION_CLASS, f)
+}
+
+/// Runs the body of a native method, turning a panic into an exception of
+/// class `class_name`, e.g. `"java/lang/IllegalStateException"`, which must
+/// have a constructor taking a message string.
+///
+/// The exception message holds the panic message and location, and the Rust
+/// backtrace if `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` enables it. If an
+/// exception is already pending, it is kept instead.
+///
+/// `f` does not have to be `UnwindSafe`: the env is only used to throw after
+/// a panic, and whatever else `f` borrowed may be left in the state the panic
+/// interrupted, just as when the panic is caught with `AssertUnwindSafe`.
+///
+/// # Safety
+///
+/// `env` must be the `JNIEnv` passed to the native method.
+pub unsafe fn catch_panic_as<T, F>(env:*mut JNIEnv, class_name:&str, f:F) -> T
+    where T: NativeReturn, F: for<'local> FnOnce(&mut Env<'local>) -> T
+{
+    install_panic_hook();
+
+    let mut env = Env::for_native_method(env);
+
+    BOUNDARY_DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| f(&mut env)));
+    BOUNDARY_DEPTH.with(|depth| depth.set(depth.get() - 1));
+
+    match result {
+        Ok(value) => value,
+        Err(payload) => {
+            throw_panic(&env, class_name, &*payload);
+            T::default_value()
+        }
+    }
+}
+
+/// Runs the body of a native method which returns a `Result`. An error is
+/// thrown as the exception made by its `ToJavaException` implementation, and
+/// a panic as by `catch_panic()`.
+///
+/// A `JniError::JavaException` returned with `?` is thrown again, so that
+/// exceptions from Java calls are passed on to the caller.
+///
+/// # Safety
+///
+/// `env` must be the `JNIEnv` passed to the native method.
+pub unsafe fn catch_result<T, E, F>(env:*mut JNIEnv, f:F) -> T
+    where T: NativeReturn, E: ToJavaException, F: for<'local> FnOnce(&mut Env<'local>) -> Result<T, E>
+{
+    catch_panic(env, |env:&mut Env| match f(env) {
+        Ok(value) => value,
+        Err(err) => {
+            throw_error(env, &err);
+            T::default_value()
+        }
+    })
+}
@@ -0,0 +1,214 @@
+//! Typed local references
+//!
+//! Each wrapper holds a non-null reference which is only valid for the
+//! `'local` lifetime of the `JNIEnv` it came from. Nullable values are
+//! returned as `Option`.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
+use std::ptr::NonNull;
+
+use consts::JNI_NULL;
+use env::Env;
+use types::*;
+use value::JValue;
+
+/// A reference to any Java object.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JObject<'local> {
+    raw: NonNull<_Jobject>,
+    lifetime: PhantomData<&'local ()>
+}
+
+impl<'local> JObject<'local> {
+    /// Wraps a raw reference, returning `None` if it is null.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be null or a valid reference for `'local`.
+    pub unsafe fn from_raw(raw:Jobject) -> Option<JObject<'local>> {
+        NonNull::new(raw).map(|raw| JObject { raw, lifetime: PhantomData })
+    }
+
+    pub fn as_raw(&self) -> Jobject {
+        self.raw.as_ptr()
+    }
+}
+
+/// Returns the raw reference of an optional object, null for `None`.
+pub fn as_raw_or_null(obj:Option<&JObject>) -> Jobject {
+    obj.map_or(JNI_NULL, |obj| obj.as_raw())
+}
+
+/// A reference type which can be checked for and converted from a `JObject`.
+pub trait Downcast<'local>: Sized {
+    /// Class name or array descriptor passed to `FindClass` for the check.
+    const CLASS_NAME: &'static str;
+
+    /// Converts without checking the class of the object.
+    ///
+    /// # Safety
+    ///
+    /// `obj` must be an instance of `CLASS_NAME`.
+    unsafe fn from_object_unchecked(obj:JObject<'local>) -> Self;
+}
+
+impl<'local> Downcast<'local> for JObject<'local> {
+    const CLASS_NAME: &'static str = "java/lang/Object";
+
+    unsafe fn from_object_unchecked(obj:JObject<'local>) -> JObject<'local> {
+        obj
+    }
+}
+
+macro_rules! reference_types {
+    ($($(#[$attr:meta])* $name:ident => $class_name:expr),*) => {
+        $(
+            $(#[$attr])*
+            #[repr(transparent)]
+            #[derive(Debug)]
+            pub struct $name<'local>(JObject<'local>);
+
+            impl<'local> $name<'local> {
+                /// Wraps a raw reference, returning `None` if it is null.
+                ///
+                /// # Safety
+                ///
+                /// `raw` must be null or a valid reference of this type for `'local`.
+                pub unsafe fn from_raw(raw:Jobject) -> Option<$name<'local>> {
+                    JObject::from_raw(raw).map($name)
+                }
+
+                /// Borrows an object as this type, without checking its class.
+                ///
+                /// # Safety
+                ///
+                /// `obj` must be an instance of this type.
+                pub unsafe fn from_object_ref_unchecked<'a>(obj:&'a JObject<'local>) -> &'a $name<'local> {
+                    &*(obj as *const JObject<'local> as *const $name<'local>)
+                }
+            }
+
+            impl<'local> Deref for $name<'local> {
+                type Target = JObject<'local>;
+
+                fn deref(&self) -> &JObject<'local> {
+                    &self.0
+                }
+            }
+
+            impl<'local> From<$name<'local>> for JObject<'local> {
+                fn from(value:$name<'local>) -> JObject<'local> {
+                    value.0
+                }
+            }
+
+            impl<'local> From<&'local $name<'local>> for JValue<'local> {
+                fn from(value:&'local $name<'local>) -> JValue<'local> {
+                    JValue::Object(Some(&value.0))
+                }
+            }
+
+            impl<'local> Downcast<'local> for $name<'local> {
+                const CLASS_NAME: &'static str = $class_name;
+
+                unsafe fn from_object_unchecked(obj:JObject<'local>) -> $name<'local> {
+                    $name(obj)
+                }
+            }
+        )*
+    }
+}
+
+reference_types! {
+    /// A reference to a `java.lang.Class`.
+    JClass => "java/lang/Class",
+    /// A reference to a `java.lang.String`.
+    JString => "java/lang/String",
+    /// A reference to a `java.lang.Throwable`.
+    JThrowable => "java/lang/Throwable",
+    /// A reference to an `Object[]`, or any other array of references.
+    JObjectArray => "[Ljava/lang/Object;",
+    JBooleanArray => "[Z",
+    JByteArray => "[B",
+    JCharArray => "[C",
+    JShortArray => "[S",
+    JIntArray => "[I",
+    JLongArray => "[J",
+    JFloatArray => "[F",
+    JDoubleArray => "[D"
+}
+
+/// A primitive array type, with the type of its elements.
+pub trait PrimitiveArray<'local>: Deref<Target = JObject<'local>> {
+    type Element;
+}
+
+macro_rules! primitive_array_elements {
+    ($($name:ident => $element:ty),*) => {
+        $(
+            impl<'local> PrimitiveArray<'local> for $name<'local> {
+                type Element = $element;
+            }
+        )*
+    }
+}
+
+primitive_array_elements! {
+    JBooleanArray => Jboolean,
+    JByteArray => Jbyte,
+    JCharArray => Jchar,
+    JShortArray => Jshort,
+    JIntArray => Jint,
+    JLongArray => Jlong,
+    JFloatArray => Jfloat,
+    JDoubleArray => Jdouble
+}
+
+/// Deletes a local reference when dropped, instead of when the native method
+/// returns or its frame is popped.
+///
+/// The guard owns the reference and only lends it out, so that it cannot be
+/// used after it has been deleted.
+pub struct AutoLocal<'a, 'local: 'a, T:Into<JObject<'local>>> {
+    obj: ManuallyDrop<T>,
+    env: &'a Env<'local>
+}
+
+impl<'a, 'local, T:Into<JObject<'local>>> AutoLocal<'a, 'local, T> {
+    pub fn new(env:&'a Env<'local>, obj:T) -> AutoLocal<'a, 'local, T> {
+        AutoLocal { obj: ManuallyDrop::new(obj), env }
+    }
+
+    /// Keeps the reference alive, returning it without deleting it.
+    pub fn forget(self) -> T {
+        let mut guard = ManuallyDrop::new(self);
+        // The guard is not dropped, so the object is taken out exactly once.
+        unsafe { ManuallyDrop::take(&mut guard.obj) }
+    }
+}
+
+impl<'a, 'local, T:Into<JObject<'local>>> Deref for AutoLocal<'a, 'local, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.obj
+    }
+}
+
+impl<'a, 'local, T:Into<JObject<'local>>> Drop for AutoLocal<'a, 'local, T> {
+    fn drop(&mut self) {
+        // The object is not used after `drop()`.
+        let obj = unsafe { ManuallyDrop::take(&mut self.obj) };
+        self.env.delete_local_ref(obj.into());
+    }
+}
+
+impl<'a, 'local, T:Into<JObject<'local>> + fmt::Debug> fmt::Debug for AutoLocal<'a, 'local, T> {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("AutoLocal").field(&*self.obj).finish()
+    }
+}
@@ -0,0 +1,148 @@
+//! Global and weak global references
+
+use std::sync::Arc;
+
+use attach::AttachArgs;
+use env::Env;
+use error::JniError;
+use objects::{AutoLocal, JObject};
+use types::*;
+use vm::JavaVm;
+
+/// A global reference, which keeps its object alive until the last clone is
+/// dropped and can be used from any thread attached to the VM.
+#[derive(Clone, Debug)]
+pub struct GlobalRef {
+    inner: Arc<GlobalRefInner>
+}
+
+#[derive(Debug)]
+struct GlobalRefInner {
+    obj: JObject<'static>,
+    vm: JavaVm
+}
+
+// Global references are valid on every thread.
+unsafe impl Send for GlobalRefInner {}
+unsafe impl Sync for GlobalRefInner {}
+
+impl Drop for GlobalRefInner {
+    fn drop(&mut self) {
+        // The reference went away with the VM.
+        if !self.vm.is_alive() {
+            return;
+        }
+
+        // Attaches for the duration of the call if this thread is not attached.
+        // No local reference is made, so another `Env` of the thread may be
+        // alive.
+        let result = self.vm.attach_current_thread(AttachArgs::default())
+            .map(|attachment| unsafe { attachment.env_unchecked() }.delete_global_ref(self.obj.as_raw()));
+
+        if let Err(err) = result {
+            warn!("Could not delete global reference: {}", err);
+        }
+    }
+}
+
+impl GlobalRef {
+    /// Creates a global reference to the object of a local reference.
+    pub fn new(env:&Env, obj:&JObject) -> Result<GlobalRef, JniError> {
+        let vm = env.get_java_vm()?;
+        let obj = unsafe { JObject::from_raw(env.new_global_ref(obj)?) }
+            .ok_or(JniError::NullResult("NewGlobalRef"))?;
+
+        Ok(GlobalRef { inner: Arc::new(GlobalRefInner { obj, vm }) })
+    }
+
+    /// Borrows the object, which can be passed to JNI functions on any
+    /// attached thread while the `GlobalRef` is alive.
+    pub fn as_obj(&self) -> &JObject<'_> {
+        &self.inner.obj
+    }
+
+    /// Creates a new local reference to the object in the frame of `env`.
+    pub fn to_local<'local>(&self, env:&Env<'local>) -> Result<JObject<'local>, JniError> {
+        env.new_local_ref(self.as_obj())?
+            .ok_or(JniError::NullResult("NewLocalRef"))
+    }
+
+    /// Returns the raw global reference.
+    pub fn as_raw(&self) -> Jobject {
+        self.inner.obj.as_raw()
+    }
+
+    /// The VM which owns the reference.
+    pub(crate) fn vm(&self) -> &JavaVm {
+        &self.inner.vm
+    }
+}
+
+/// A weak global reference, which does not keep its object from being
+/// garbage collected. It is deleted when the last clone is dropped.
+#[derive(Clone, Debug)]
+pub struct WeakRef {
+    inner: Arc<WeakRefInner>
+}
+
+#[derive(Debug)]
+struct WeakRefInner {
+    weak: JObject<'static>,
+    vm: JavaVm
+}
+
+// Weak global references are valid on every thread as well.
+unsafe impl Send for WeakRefInner {}
+unsafe impl Sync for WeakRefInner {}
+
+impl Drop for WeakRefInner {
+    fn drop(&mut self) {
+        if !self.vm.is_alive() {
+            return;
+        }
+
+        let result = self.vm.attach_current_thread(AttachArgs::default())
+            .map(|attachment| unsafe { attachment.env_unchecked() }.delete_weak_global_ref(self.weak.as_raw()));
+
+        if let Err(err) = result {
+            warn!("Could not delete weak global reference: {}", err);
+        }
+    }
+}
+
+impl WeakRef {
+    /// Creates a weak global reference to the object of a local reference.
+    pub fn new(env:&Env, obj:&JObject) -> Result<WeakRef, JniError> {
+        let vm = env.get_java_vm()?;
+        let weak = unsafe { JObject::from_raw(env.new_weak_global_ref(obj)?) }
+            .ok_or(JniError::NullResult("NewWeakGlobalRef"))?;
+
+        Ok(WeakRef { inner: Arc::new(WeakRefInner { weak, vm }) })
+    }
+
+    /// Returns true if the object has been garbage collected.
+    pub fn is_collected(&self, env:&Env) -> Result<bool, JniError> {
+        env.is_same_object(Some(&self.inner.weak), None)
+    }
+
+    /// Creates a local reference to the object, or returns `None` if it has
+    /// been garbage collected.
+    pub fn upgrade_local<'local>(&self, env:&Env<'local>) -> Result<Option<JObject<'local>>, JniError> {
+        if self.is_collected(env)? {
+            return Ok(None);
+        }
+
+        // The object may still be collected before the new reference is made,
+        // in which case it is null.
+        env.new_local_ref(&self.inner.weak)
+    }
+
+    /// Creates a global reference to the object, or returns `None` if it has
+    /// been garbage collected.
+    pub fn upgrade_global(&self, env:&Env) -> Result<Option<GlobalRef>, JniError> {
+        match self.upgrade_local(env)? {
+            Some(local) => GlobalRef::new(env, &AutoLocal::new(env, local)).map(Some),
+            None => Ok(None)
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Java VM shutdown
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use attach::AttachArgs;
+use error::{check_result, JniError};
+use env::Env;
+use ffi;
+use types::*;
+use value::JValue;
+use vm::JavaVm;
+
+/// What happens to a Java VM when the `JNI` instance which created it is
+/// dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropBehavior {
+    /// Calls `DestroyJavaVM`, which blocks until all non-daemon Java threads
+    /// have finished. This is the default.
+    Destroy,
+    /// Leaves the VM running. Its threads are terminated with the process.
+    Leak,
+    /// Calls `Runtime.halt()` with the given status, which terminates the
+    /// process immediately.
+    Halt(Jint)
+}
+
+/// How `Runtime` is asked to terminate the process before the VM is destroyed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuntimeExit {
+    /// `Runtime.exit()`, which runs shutdown hooks first.
+    Exit(Jint),
+    /// `Runtime.halt()`, which does not.
+    Halt(Jint)
+}
+
+/// Options for `JNI::shutdown()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ShutdownOptions {
+    timeout: Option<Duration>,
+    runtime_exit: Option<RuntimeExit>
+}
+
+impl ShutdownOptions {
+    pub fn new() -> ShutdownOptions {
+        ShutdownOptions::default()
+    }
+
+    /// Calls `DestroyJavaVM` on a dedicated thread, and gives up waiting for it
+    /// after `timeout`. The VM is leaked in that case.
+    pub fn timeout(mut self, timeout:Duration) -> ShutdownOptions {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Calls `Runtime.exit()` instead of destroying the VM. This does not
+    /// return unless the call fails, in which case the VM is destroyed and the
+    /// error is returned.
+    pub fn runtime_exit(mut self, status:Jint) -> ShutdownOptions {
+        self.runtime_exit = Some(RuntimeExit::Exit(status));
+        self
+    }
+
+    /// Calls `Runtime.halt()` instead of destroying the VM. This does not
+    /// return unless the call fails, in which case the VM is destroyed and the
+    /// error is returned.
+    pub fn runtime_halt(mut self, status:Jint) -> ShutdownOptions {
+        self.runtime_exit = Some(RuntimeExit::Halt(status));
+        self
+    }
+}
+
+/// Shuts down `vm` as configured by `options`.
+///
+/// If `Runtime` cannot be called to terminate the process, the VM is destroyed
+/// as without `runtime_exit`, and the error of the call is returned.
+///
+/// # Safety
+///
+/// The VM must not be used afterwards, through this or any other handle.
+pub unsafe fn shutdown(vm:&JavaVm, options:&ShutdownOptions) -> Result<(), JniError> {
+    let runtime_result = match options.runtime_exit {
+        Some(runtime_exit) => exit_runtime(vm, runtime_exit),
+        None => Ok(())
+    };
+
+    if let Err(ref error) = runtime_result {
+        warn!("Destroying the Java VM, as Runtime could not terminate it: {}", error);
+    }
+
+    destroy(vm, options.timeout)?;
+
+    runtime_result
+}
+
+/// Calls `Runtime`, which only returns if the call fails.
+unsafe fn exit_runtime(vm:&JavaVm, runtime_exit:RuntimeExit) -> Result<(), JniError> {
+    let attachment = vm.attach_current_thread(AttachArgs::new())?;
+    // The VM goes away, together with the frames of any other `Env`.
+    let env = attachment.env_unchecked();
+
+    match runtime_exit {
+        RuntimeExit::Exit(status) => call_runtime(&env, "exit", status),
+        RuntimeExit::Halt(status) => call_runtime(&env, "halt", status)
+    }
+}
+
+/// Calls `DestroyJavaVM`, on a dedicated thread if there is a timeout.
+unsafe fn destroy(vm:&JavaVm, timeout:Option<Duration>) -> Result<(), JniError> {
+    let jvm = vm.as_raw();
+
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => {
+            vm.set_destroyed();
+            return check_result(ffi::destroy_java_vm(&*jvm));
+        }
+    };
+
+    // DestroyJavaVM waits for all other non-daemon threads, including this one
+    // if it stays attached.
+    if vm.is_current_thread_attached() {
+        vm.detach_current_thread()?;
+    }
+
+    vm.set_destroyed();
+
+    let (sender, receiver) = mpsc::channel();
+    // The handle keeps the library loaded until `DestroyJavaVM` returns, even
+    // after a timeout.
+    let target = vm.clone();
+
+    thread::spawn(move || {
+        let result = unsafe { ffi::destroy_java_vm(&*target.as_raw()) };
+        let _ = sender.send(result);
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => check_result(result),
+        Err(_) => Err(JniError::ShutdownTimeout(timeout))
+    }
+}
+
+/// Calls `Runtime.getRuntime().<method>(status)`.
+pub fn call_runtime(env:&Env, method:&str, status:Jint) -> Result<(), JniError> {
+    let runtime_class = env.find_class("java/lang/Runtime")?;
+    let get_runtime = env.get_static_method_id(&runtime_class, "getRuntime", "()Ljava/lang/Runtime;")?;
+    let runtime = env.call_static_object_method_a(&runtime_class, get_runtime, &[])?
+        .ok_or(JniError::NullResult("Runtime.getRuntime()"))?;
+    let exit = env.get_method_id(&runtime_class, method, "(I)V")?;
+
+    env.call_void_method_a(&runtime, exit, &[JValue::Int(status)])
+}
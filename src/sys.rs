@@ -0,0 +1,386 @@
+//! Raw JNI declarations, matching `jni.h` up to JNI 24
+//!
+//! All function pointers use the `JNICALL` calling convention, which is
+//! `extern "system"` in Rust. Variadic functions are always `extern "C"`.
+
+use libc::{c_char, c_void};
+
+use types::*;
+
+#[allow(non_camel_case_types)]
+pub type va_list = *mut c_void;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct JNINativeMethod {
+    pub name: *mut c_char,
+    pub signature: *mut c_char,
+    pub fn_ptr: *mut c_void
+}
+
+/// The function table of a `JNIEnv`, in `jni.h` order.
+#[repr(C)]
+pub struct JNINativeInterface {
+    pub reserved0: *mut c_void,
+    pub reserved1: *mut c_void,
+    pub reserved2: *mut c_void,
+
+    pub reserved3: *mut c_void,
+    pub get_version: unsafe extern "system" fn(env:*mut JNIEnv) -> Jint,
+
+    pub define_class: unsafe extern "system" fn(env:*mut JNIEnv, name:*const c_char, loader:Jobject, buf:*const Jbyte, len:Jsize) -> Jclass,
+    pub find_class: unsafe extern "system" fn(env:*mut JNIEnv, name:*const c_char) -> Jclass,
+
+    pub from_reflected_method: unsafe extern "system" fn(env:*mut JNIEnv, method:Jobject) -> JmethodID,
+    pub from_reflected_field: unsafe extern "system" fn(env:*mut JNIEnv, field:Jobject) -> JfieldID,
+
+    pub to_reflected_method: unsafe extern "system" fn(env:*mut JNIEnv, cls:Jclass, method_id:JmethodID, is_static:Jboolean) -> Jobject,
+
+    pub get_superclass: unsafe extern "system" fn(env:*mut JNIEnv, sub:Jclass) -> Jclass,
+    pub is_assignable_from: unsafe extern "system" fn(env:*mut JNIEnv, sub:Jclass, sup:Jclass) -> Jboolean,
+
+    pub to_reflected_field: unsafe extern "system" fn(env:*mut JNIEnv, cls:Jclass, field_id:JfieldID, is_static:Jboolean) -> Jobject,
+
+    pub throw: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jthrowable) -> Jint,
+    pub throw_new: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, msg:*const c_char) -> Jint,
+    pub exception_occurred: unsafe extern "system" fn(env:*mut JNIEnv) -> Jthrowable,
+    pub exception_describe: unsafe extern "system" fn(env:*mut JNIEnv),
+    pub exception_clear: unsafe extern "system" fn(env:*mut JNIEnv),
+    pub fatal_error: unsafe extern "system" fn(env:*mut JNIEnv, msg:*const c_char),
+
+    pub push_local_frame: unsafe extern "system" fn(env:*mut JNIEnv, capacity:Jint) -> Jint,
+    pub pop_local_frame: unsafe extern "system" fn(env:*mut JNIEnv, result:Jobject) -> Jobject,
+
+    pub new_global_ref: unsafe extern "system" fn(env:*mut JNIEnv, lobj:Jobject) -> Jobject,
+    pub delete_global_ref: unsafe extern "system" fn(env:*mut JNIEnv, gref:Jobject),
+    pub delete_local_ref: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject),
+    pub is_same_object: unsafe extern "system" fn(env:*mut JNIEnv, obj1:Jobject, obj2:Jobject) -> Jboolean,
+    pub new_local_ref: unsafe extern "system" fn(env:*mut JNIEnv, obj_ref:Jobject) -> Jobject,
+    pub ensure_local_capacity: unsafe extern "system" fn(env:*mut JNIEnv, capacity:Jint) -> Jint,
+
+    pub alloc_object: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass) -> Jobject,
+    pub new_object: unsafe extern "C" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, ...) -> Jobject,
+    pub new_object_v: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jobject,
+    pub new_object_a: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jobject,
+
+    pub get_object_class: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject) -> Jclass,
+    pub is_instance_of: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass) -> Jboolean,
+
+    pub get_method_id: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, name:*const c_char, sig:*const c_char) -> JmethodID,
+
+    pub call_object_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, ...) -> Jobject,
+    pub call_object_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:va_list) -> Jobject,
+    pub call_object_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:*const Jvalue) -> Jobject,
+
+    pub call_boolean_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, ...) -> Jboolean,
+    pub call_boolean_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:va_list) -> Jboolean,
+    pub call_boolean_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:*const Jvalue) -> Jboolean,
+
+    pub call_byte_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, ...) -> Jbyte,
+    pub call_byte_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:va_list) -> Jbyte,
+    pub call_byte_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:*const Jvalue) -> Jbyte,
+
+    pub call_char_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, ...) -> Jchar,
+    pub call_char_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:va_list) -> Jchar,
+    pub call_char_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:*const Jvalue) -> Jchar,
+
+    pub call_short_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, ...) -> Jshort,
+    pub call_short_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:va_list) -> Jshort,
+    pub call_short_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:*const Jvalue) -> Jshort,
+
+    pub call_int_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, ...) -> Jint,
+    pub call_int_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:va_list) -> Jint,
+    pub call_int_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:*const Jvalue) -> Jint,
+
+    pub call_long_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, ...) -> Jlong,
+    pub call_long_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:va_list) -> Jlong,
+    pub call_long_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:*const Jvalue) -> Jlong,
+
+    pub call_float_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, ...) -> Jfloat,
+    pub call_float_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:va_list) -> Jfloat,
+    pub call_float_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:*const Jvalue) -> Jfloat,
+
+    pub call_double_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, ...) -> Jdouble,
+    pub call_double_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:va_list) -> Jdouble,
+    pub call_double_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:*const Jvalue) -> Jdouble,
+
+    pub call_void_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, ...),
+    pub call_void_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:va_list),
+    pub call_void_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, method_id:JmethodID, args:*const Jvalue),
+
+    pub call_nonvirtual_object_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, ...) -> Jobject,
+    pub call_nonvirtual_object_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jobject,
+    pub call_nonvirtual_object_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jobject,
+
+    pub call_nonvirtual_boolean_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, ...) -> Jboolean,
+    pub call_nonvirtual_boolean_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jboolean,
+    pub call_nonvirtual_boolean_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jboolean,
+
+    pub call_nonvirtual_byte_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, ...) -> Jbyte,
+    pub call_nonvirtual_byte_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jbyte,
+    pub call_nonvirtual_byte_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jbyte,
+
+    pub call_nonvirtual_char_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, ...) -> Jchar,
+    pub call_nonvirtual_char_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jchar,
+    pub call_nonvirtual_char_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jchar,
+
+    pub call_nonvirtual_short_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, ...) -> Jshort,
+    pub call_nonvirtual_short_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jshort,
+    pub call_nonvirtual_short_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jshort,
+
+    pub call_nonvirtual_int_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, ...) -> Jint,
+    pub call_nonvirtual_int_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jint,
+    pub call_nonvirtual_int_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jint,
+
+    pub call_nonvirtual_long_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, ...) -> Jlong,
+    pub call_nonvirtual_long_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jlong,
+    pub call_nonvirtual_long_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jlong,
+
+    pub call_nonvirtual_float_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, ...) -> Jfloat,
+    pub call_nonvirtual_float_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jfloat,
+    pub call_nonvirtual_float_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jfloat,
+
+    pub call_nonvirtual_double_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, ...) -> Jdouble,
+    pub call_nonvirtual_double_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jdouble,
+    pub call_nonvirtual_double_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jdouble,
+
+    pub call_nonvirtual_void_method: unsafe extern "C" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, ...),
+    pub call_nonvirtual_void_method_v: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:va_list),
+    pub call_nonvirtual_void_method_a: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, clazz:Jclass, method_id:JmethodID, args:*const Jvalue),
+
+    pub get_field_id: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, name:*const c_char, sig:*const c_char) -> JfieldID,
+
+    pub get_object_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID) -> Jobject,
+    pub get_boolean_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID) -> Jboolean,
+    pub get_byte_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID) -> Jbyte,
+    pub get_char_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID) -> Jchar,
+    pub get_short_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID) -> Jshort,
+    pub get_int_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID) -> Jint,
+    pub get_long_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID) -> Jlong,
+    pub get_float_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID) -> Jfloat,
+    pub get_double_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID) -> Jdouble,
+
+    pub set_object_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID, val:Jobject),
+    pub set_boolean_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID, val:Jboolean),
+    pub set_byte_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID, val:Jbyte),
+    pub set_char_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID, val:Jchar),
+    pub set_short_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID, val:Jshort),
+    pub set_int_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID, val:Jint),
+    pub set_long_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID, val:Jlong),
+    pub set_float_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID, val:Jfloat),
+    pub set_double_field: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject, field_id:JfieldID, val:Jdouble),
+
+    pub get_static_method_id: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, name:*const c_char, sig:*const c_char) -> JmethodID,
+
+    pub call_static_object_method: unsafe extern "C" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, ...) -> Jobject,
+    pub call_static_object_method_v: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jobject,
+    pub call_static_object_method_a: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jobject,
+
+    pub call_static_boolean_method: unsafe extern "C" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, ...) -> Jboolean,
+    pub call_static_boolean_method_v: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jboolean,
+    pub call_static_boolean_method_a: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jboolean,
+
+    pub call_static_byte_method: unsafe extern "C" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, ...) -> Jbyte,
+    pub call_static_byte_method_v: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jbyte,
+    pub call_static_byte_method_a: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jbyte,
+
+    pub call_static_char_method: unsafe extern "C" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, ...) -> Jchar,
+    pub call_static_char_method_v: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jchar,
+    pub call_static_char_method_a: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jchar,
+
+    pub call_static_short_method: unsafe extern "C" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, ...) -> Jshort,
+    pub call_static_short_method_v: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jshort,
+    pub call_static_short_method_a: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jshort,
+
+    pub call_static_int_method: unsafe extern "C" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, ...) -> Jint,
+    pub call_static_int_method_v: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jint,
+    pub call_static_int_method_a: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jint,
+
+    pub call_static_long_method: unsafe extern "C" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, ...) -> Jlong,
+    pub call_static_long_method_v: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jlong,
+    pub call_static_long_method_a: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jlong,
+
+    pub call_static_float_method: unsafe extern "C" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, ...) -> Jfloat,
+    pub call_static_float_method_v: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jfloat,
+    pub call_static_float_method_a: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jfloat,
+
+    pub call_static_double_method: unsafe extern "C" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, ...) -> Jdouble,
+    pub call_static_double_method_v: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:va_list) -> Jdouble,
+    pub call_static_double_method_a: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, method_id:JmethodID, args:*const Jvalue) -> Jdouble,
+
+    pub call_static_void_method: unsafe extern "C" fn(env:*mut JNIEnv, cls:Jclass, method_id:JmethodID, ...),
+    pub call_static_void_method_v: unsafe extern "system" fn(env:*mut JNIEnv, cls:Jclass, method_id:JmethodID, args:va_list),
+    pub call_static_void_method_a: unsafe extern "system" fn(env:*mut JNIEnv, cls:Jclass, method_id:JmethodID, args:*const Jvalue),
+
+    pub get_static_field_id: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, name:*const c_char, sig:*const c_char) -> JfieldID,
+    pub get_static_object_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID) -> Jobject,
+    pub get_static_boolean_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID) -> Jboolean,
+    pub get_static_byte_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID) -> Jbyte,
+    pub get_static_char_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID) -> Jchar,
+    pub get_static_short_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID) -> Jshort,
+    pub get_static_int_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID) -> Jint,
+    pub get_static_long_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID) -> Jlong,
+    pub get_static_float_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID) -> Jfloat,
+    pub get_static_double_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID) -> Jdouble,
+
+    pub set_static_object_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID, value:Jobject),
+    pub set_static_boolean_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID, value:Jboolean),
+    pub set_static_byte_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID, value:Jbyte),
+    pub set_static_char_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID, value:Jchar),
+    pub set_static_short_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID, value:Jshort),
+    pub set_static_int_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID, value:Jint),
+    pub set_static_long_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID, value:Jlong),
+    pub set_static_float_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID, value:Jfloat),
+    pub set_static_double_field: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, field_id:JfieldID, value:Jdouble),
+
+    pub new_string: unsafe extern "system" fn(env:*mut JNIEnv, unicode:*const Jchar, len:Jsize) -> Jstring,
+    pub get_string_length: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring) -> Jsize,
+    pub get_string_chars: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring, is_copy:*mut Jboolean) -> *const Jchar,
+    pub release_string_chars: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring, chars:*const Jchar),
+
+    pub new_string_utf: unsafe extern "system" fn(env:*mut JNIEnv, utf:*const c_char) -> Jstring,
+    pub get_string_utf_length: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring) -> Jsize,
+    pub get_string_utf_chars: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring, is_copy:*mut Jboolean) -> *const c_char,
+    pub release_string_utf_chars: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring, chars:*const c_char),
+
+    pub get_array_length: unsafe extern "system" fn(env:*mut JNIEnv, array:Jarray) -> Jsize,
+
+    pub new_object_array: unsafe extern "system" fn(env:*mut JNIEnv, len:Jsize, clazz:Jclass, init:Jobject) -> JobjectArray,
+    pub get_object_array_element: unsafe extern "system" fn(env:*mut JNIEnv, array:JobjectArray, index:Jsize) -> Jobject,
+    pub set_object_array_element: unsafe extern "system" fn(env:*mut JNIEnv, array:JobjectArray, index:Jsize, val:Jobject),
+
+    pub new_boolean_array: unsafe extern "system" fn(env:*mut JNIEnv, len:Jsize) -> JbooleanArray,
+    pub new_byte_array: unsafe extern "system" fn(env:*mut JNIEnv, len:Jsize) -> JbyteArray,
+    pub new_char_array: unsafe extern "system" fn(env:*mut JNIEnv, len:Jsize) -> JcharArray,
+    pub new_short_array: unsafe extern "system" fn(env:*mut JNIEnv, len:Jsize) -> JshortArray,
+    pub new_int_array: unsafe extern "system" fn(env:*mut JNIEnv, len:Jsize) -> JintArray,
+    pub new_long_array: unsafe extern "system" fn(env:*mut JNIEnv, len:Jsize) -> JlongArray,
+    pub new_float_array: unsafe extern "system" fn(env:*mut JNIEnv, len:Jsize) -> JfloatArray,
+    pub new_double_array: unsafe extern "system" fn(env:*mut JNIEnv, len:Jsize) -> JdoubleArray,
+
+    pub get_boolean_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JbooleanArray, is_copy:*mut Jboolean) -> *mut Jboolean,
+    pub get_byte_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JbyteArray, is_copy:*mut Jboolean) -> *mut Jbyte,
+    pub get_char_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JcharArray, is_copy:*mut Jboolean) -> *mut Jchar,
+    pub get_short_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JshortArray, is_copy:*mut Jboolean) -> *mut Jshort,
+    pub get_int_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JintArray, is_copy:*mut Jboolean) -> *mut Jint,
+    pub get_long_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JlongArray, is_copy:*mut Jboolean) -> *mut Jlong,
+    pub get_float_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JfloatArray, is_copy:*mut Jboolean) -> *mut Jfloat,
+    pub get_double_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JdoubleArray, is_copy:*mut Jboolean) -> *mut Jdouble,
+
+    pub release_boolean_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JbooleanArray, elems:*mut Jboolean, mode:Jint),
+    pub release_byte_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JbyteArray, elems:*mut Jbyte, mode:Jint),
+    pub release_char_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JcharArray, elems:*mut Jchar, mode:Jint),
+    pub release_short_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JshortArray, elems:*mut Jshort, mode:Jint),
+    pub release_int_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JintArray, elems:*mut Jint, mode:Jint),
+    pub release_long_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JlongArray, elems:*mut Jlong, mode:Jint),
+    pub release_float_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JfloatArray, elems:*mut Jfloat, mode:Jint),
+    pub release_double_array_elements: unsafe extern "system" fn(env:*mut JNIEnv, array:JdoubleArray, elems:*mut Jdouble, mode:Jint),
+
+    pub get_boolean_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JbooleanArray, start:Jsize, l:Jsize, buf:*mut Jboolean),
+    pub get_byte_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JbyteArray, start:Jsize, len:Jsize, buf:*mut Jbyte),
+    pub get_char_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JcharArray, start:Jsize, len:Jsize, buf:*mut Jchar),
+    pub get_short_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JshortArray, start:Jsize, len:Jsize, buf:*mut Jshort),
+    pub get_int_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JintArray, start:Jsize, len:Jsize, buf:*mut Jint),
+    pub get_long_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JlongArray, start:Jsize, len:Jsize, buf:*mut Jlong),
+    pub get_float_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JfloatArray, start:Jsize, len:Jsize, buf:*mut Jfloat),
+    pub get_double_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JdoubleArray, start:Jsize, len:Jsize, buf:*mut Jdouble),
+
+    pub set_boolean_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JbooleanArray, start:Jsize, l:Jsize, buf:*const Jboolean),
+    pub set_byte_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JbyteArray, start:Jsize, len:Jsize, buf:*const Jbyte),
+    pub set_char_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JcharArray, start:Jsize, len:Jsize, buf:*const Jchar),
+    pub set_short_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JshortArray, start:Jsize, len:Jsize, buf:*const Jshort),
+    pub set_int_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JintArray, start:Jsize, len:Jsize, buf:*const Jint),
+    pub set_long_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JlongArray, start:Jsize, len:Jsize, buf:*const Jlong),
+    pub set_float_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JfloatArray, start:Jsize, len:Jsize, buf:*const Jfloat),
+    pub set_double_array_region: unsafe extern "system" fn(env:*mut JNIEnv, array:JdoubleArray, start:Jsize, len:Jsize, buf:*const Jdouble),
+
+    pub register_natives: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass, methods:*const JNINativeMethod, n_methods:Jint) -> Jint,
+    pub unregister_natives: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass) -> Jint,
+
+    pub monitor_enter: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject) -> Jint,
+    pub monitor_exit: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject) -> Jint,
+
+    pub get_java_vm: unsafe extern "system" fn(env:*mut JNIEnv, vm:*mut *mut JavaVM) -> Jint,
+
+    pub get_string_region: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring, start:Jsize, len:Jsize, buf:*mut Jchar),
+    pub get_string_utf_region: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring, start:Jsize, len:Jsize, buf:*mut c_char),
+
+    pub get_primitive_array_critical: unsafe extern "system" fn(env:*mut JNIEnv, array:Jarray, is_copy:*mut Jboolean) -> *mut c_void,
+    pub release_primitive_array_critical: unsafe extern "system" fn(env:*mut JNIEnv, array:Jarray, carray:*mut c_void, mode:Jint),
+
+    pub get_string_critical: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring, is_copy:*mut Jboolean) -> *const Jchar,
+    pub release_string_critical: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring, cstring:*const Jchar),
+
+    pub new_weak_global_ref: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject) -> Jweak,
+    pub delete_weak_global_ref: unsafe extern "system" fn(env:*mut JNIEnv, obj_ref:Jweak),
+
+    pub exception_check: unsafe extern "system" fn(env:*mut JNIEnv) -> Jboolean,
+
+    pub new_direct_byte_buffer: unsafe extern "system" fn(env:*mut JNIEnv, address:*mut c_void, capacity:Jlong) -> Jobject,
+    pub get_direct_buffer_address: unsafe extern "system" fn(env:*mut JNIEnv, buf:Jobject) -> *mut c_void,
+    pub get_direct_buffer_capacity: unsafe extern "system" fn(env:*mut JNIEnv, buf:Jobject) -> Jlong,
+
+    // JNI 1.6
+    pub get_object_ref_type: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject) -> JobjectRefType,
+
+    // JNI 9
+    pub get_module: unsafe extern "system" fn(env:*mut JNIEnv, clazz:Jclass) -> Jobject,
+
+    // JNI 21
+    pub is_virtual_thread: unsafe extern "system" fn(env:*mut JNIEnv, obj:Jobject) -> Jboolean,
+
+    // JNI 24
+    pub get_string_utf_length_as_long: unsafe extern "system" fn(env:*mut JNIEnv, string:Jstring) -> Jlong
+}
+
+#[repr(C)]
+pub struct JNIEnv {
+    pub functions: *const JNINativeInterface
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct JavaVMOption {
+    pub option_string: *const c_char,
+    pub extra_info: *mut c_void
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct JavaVMInitArgs {
+    pub version: Jint,
+    pub n_options: Jint,
+    pub options: *mut JavaVMOption,
+    pub ignore_unrecognized: Jboolean
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct JavaVMAttachArgs {
+    pub version: Jint,
+    pub name: *const c_char,
+    pub group: Jobject
+}
+
+/// The function table of a `JavaVM`, in `jni.h` order.
+#[repr(C)]
+pub struct JNIInvokeInterface {
+    pub reserved0: *mut c_void,
+    pub reserved1: *mut c_void,
+    pub reserved2: *mut c_void,
+
+    pub destroy_java_vm: unsafe extern "system" fn(vm:*mut JavaVM) -> Jint,
+    pub attach_current_thread: unsafe extern "system" fn(vm:*mut JavaVM, penv:*mut *mut c_void, args:*mut c_void) -> Jint,
+    pub detach_current_thread: unsafe extern "system" fn(vm:*mut JavaVM) -> Jint,
+    pub get_env: unsafe extern "system" fn(vm:*mut JavaVM, penv:*mut *mut c_void, version:Jint) -> Jint,
+    pub attach_current_thread_as_daemon: unsafe extern "system" fn(vm:*mut JavaVM, penv:*mut *mut c_void, args:*mut c_void) -> Jint
+}
+
+#[repr(C)]
+pub struct JavaVM {
+    pub functions: *const JNIInvokeInterface
+}
+
+pub type JNICreateJavaVM = unsafe extern "system" fn(pvm:*mut *mut JavaVM, penv:*mut *mut c_void, args:*mut c_void) -> Jint;
+pub type JNIGetDefaultJavaVMInitArgs = unsafe extern "system" fn(args:*mut c_void) -> Jint;
+pub type JNIGetCreatedJavaVMs = unsafe extern "system" fn(vm_buf:*mut *mut JavaVM, buf_len:Jsize, n_vms:*mut Jsize) -> Jint;
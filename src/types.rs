@@ -1,20 +1,13 @@
 //! JNI types
-
-use libc::{c_long, c_longlong};
-
-#[cfg(target_arch = "x86_64")]
-pub type Jint = c_long;
-
-#[cfg(target_arch = "x86_64")]
-pub type Jlong = c_longlong;
-
-pub type Jbyte = i8;
-
-#[cfg(target_arch = "x86_64")]
-pub type Jpointer = u64;
+//!
+//! The primitive types have the same width on every target, references are
+//! opaque pointers so they follow the pointer width of the target.
 
 // JNI Types
 
+pub type Jint = i32;
+pub type Jlong = i64;
+pub type Jbyte = i8;
 pub type Jboolean = u8;
 pub type Jchar = u16;
 pub type Jshort = i16;
@@ -22,7 +15,13 @@ pub type Jfloat = f32;
 pub type Jdouble = f64;
 pub type Jsize = Jint;
 
-pub type Jobject = Jpointer;
+// Opaque types behind the reference pointers
+
+pub enum _Jobject {}
+pub enum _JfieldID {}
+pub enum _JmethodID {}
+
+pub type Jobject = *mut _Jobject;
 
 pub type Jclass = Jobject;
 pub type Jthrowable = Jobject;
@@ -39,21 +38,33 @@ pub type JfloatArray = Jarray;
 pub type JdoubleArray = Jarray;
 pub type JobjectArray = Jarray;
 
-pub type Jtweak = Jobject;
+pub type Jweak = Jobject;
 
-pub type Jvalue = Jobject; // union in C/C++
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union Jvalue {
+    pub z: Jboolean,
+    pub b: Jbyte,
+    pub c: Jchar,
+    pub s: Jshort,
+    pub i: Jint,
+    pub j: Jlong,
+    pub f: Jfloat,
+    pub d: Jdouble,
+    pub l: Jobject
+}
 
-pub type JfieldID = Jpointer;
-pub type JmethodID = Jpointer;
+pub type JfieldID = *mut _JfieldID;
+pub type JmethodID = *mut _JmethodID;
 
 #[repr(C)]
 pub enum JobjectRefType {
     JNIInvalidRefType           = 0,
     JNILocalRefType             = 1,
     JNIGlobalRefType            = 2,
-    JNIWeakGlobalRefType        = 3 
+    JNIWeakGlobalRefType        = 3
 }
 
-pub fn is_null(p:Jpointer) -> bool {
-    p == 0u64
+pub fn is_null<T>(p:*mut T) -> bool {
+    p.is_null()
 }
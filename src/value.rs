@@ -0,0 +1,98 @@
+//! Typed method arguments
+
+use objects::{as_raw_or_null, JObject};
+use types::*;
+
+/// A method argument, converted into a `Jvalue` when the call is made.
+///
+/// Objects are borrowed, so that passing them does not give up the reference.
+/// There is no `PartialEq`, as references to the same object may differ; use
+/// `Env::is_same_object()` to compare objects.
+#[derive(Clone, Copy, Debug)]
+pub enum JValue<'local> {
+    Bool(bool),
+    Byte(Jbyte),
+    Char(Jchar),
+    Short(Jshort),
+    Int(Jint),
+    Long(Jlong),
+    Float(Jfloat),
+    Double(Jdouble),
+    Object(Option<&'local JObject<'local>>)
+}
+
+impl<'local> JValue<'local> {
+    pub fn to_jvalue(self) -> Jvalue {
+        match self {
+            JValue::Bool(z) => Jvalue { z: z as Jboolean },
+            JValue::Byte(b) => Jvalue { b },
+            JValue::Char(c) => Jvalue { c },
+            JValue::Short(s) => Jvalue { s },
+            JValue::Int(i) => Jvalue { i },
+            JValue::Long(j) => Jvalue { j },
+            JValue::Float(f) => Jvalue { f },
+            JValue::Double(d) => Jvalue { d },
+            JValue::Object(l) => Jvalue { l: as_raw_or_null(l) }
+        }
+    }
+}
+
+impl<'local> From<JValue<'local>> for Jvalue {
+    fn from(value:JValue<'local>) -> Jvalue {
+        value.to_jvalue()
+    }
+}
+
+impl<'local> From<bool> for JValue<'local> {
+    fn from(value:bool) -> JValue<'local> {
+        JValue::Bool(value)
+    }
+}
+
+impl<'local> From<Jbyte> for JValue<'local> {
+    fn from(value:Jbyte) -> JValue<'local> {
+        JValue::Byte(value)
+    }
+}
+
+impl<'local> From<Jchar> for JValue<'local> {
+    fn from(value:Jchar) -> JValue<'local> {
+        JValue::Char(value)
+    }
+}
+
+impl<'local> From<Jshort> for JValue<'local> {
+    fn from(value:Jshort) -> JValue<'local> {
+        JValue::Short(value)
+    }
+}
+
+impl<'local> From<Jint> for JValue<'local> {
+    fn from(value:Jint) -> JValue<'local> {
+        JValue::Int(value)
+    }
+}
+
+impl<'local> From<Jlong> for JValue<'local> {
+    fn from(value:Jlong) -> JValue<'local> {
+        JValue::Long(value)
+    }
+}
+
+impl<'local> From<Jfloat> for JValue<'local> {
+    fn from(value:Jfloat) -> JValue<'local> {
+        JValue::Float(value)
+    }
+}
+
+impl<'local> From<Jdouble> for JValue<'local> {
+    fn from(value:Jdouble) -> JValue<'local> {
+        JValue::Double(value)
+    }
+}
+
+impl<'local> From<&'local JObject<'local>> for JValue<'local> {
+    fn from(value:&'local JObject<'local>) -> JValue<'local> {
+        JValue::Object(Some(value))
+    }
+}
@@ -0,0 +1,87 @@
+//! JNI versions
+
+use std::fmt;
+
+use consts::*;
+use types::*;
+
+/// The JNI versions defined by `jni.h`.
+///
+/// Versions are ordered, so code can check for the presence of newer JNI
+/// functions with e.g. `version >= JniVersion::V21`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JniVersion {
+    V1_1,
+    V1_2,
+    V1_4,
+    V1_6,
+    V1_8,
+    V9,
+    V10,
+    V19,
+    V20,
+    V21,
+    V24
+}
+
+const VERSIONS:[JniVersion; 11] = [
+    JniVersion::V1_1,
+    JniVersion::V1_2,
+    JniVersion::V1_4,
+    JniVersion::V1_6,
+    JniVersion::V1_8,
+    JniVersion::V9,
+    JniVersion::V10,
+    JniVersion::V19,
+    JniVersion::V20,
+    JniVersion::V21,
+    JniVersion::V24
+];
+
+impl JniVersion {
+    /// All known versions, oldest first.
+    pub fn all() -> &'static [JniVersion] {
+        &VERSIONS
+    }
+
+    pub fn to_jint(self) -> Jint {
+        match self {
+            JniVersion::V1_1 => JNI_VERSION_1_1,
+            JniVersion::V1_2 => JNI_VERSION_1_2,
+            JniVersion::V1_4 => JNI_VERSION_1_4,
+            JniVersion::V1_6 => JNI_VERSION_1_6,
+            JniVersion::V1_8 => JNI_VERSION_1_8,
+            JniVersion::V9 => JNI_VERSION_9,
+            JniVersion::V10 => JNI_VERSION_10,
+            JniVersion::V19 => JNI_VERSION_19,
+            JniVersion::V20 => JNI_VERSION_20,
+            JniVersion::V21 => JNI_VERSION_21,
+            JniVersion::V24 => JNI_VERSION_24
+        }
+    }
+
+    /// Maps a raw version number to the matching version, if known.
+    pub fn from_jint(version:Jint) -> Option<JniVersion> {
+        VERSIONS.iter().cloned().find(|v| v.to_jint() == version)
+    }
+
+    /// Maps a raw version number reported by a VM to the newest known version
+    /// it includes. A VM newer than this crate reports a version which is not
+    /// listed here, but still supports all known functions.
+    pub fn supported_by(version:Jint) -> Option<JniVersion> {
+        VERSIONS.iter().cloned().rev().find(|v| v.to_jint() <= version)
+    }
+}
+
+impl fmt::Display for JniVersion {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        let version = self.to_jint();
+        let (major, minor) = (version >> 16, version & 0xffff);
+
+        if major == 1 {
+            write!(f, "1.{}", minor)
+        } else {
+            write!(f, "{}", major)
+        }
+    }
+}
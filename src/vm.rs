@@ -0,0 +1,161 @@
+//! Shareable Java VM handle
+
+use shared_library::dynamic_library::DynamicLibrary;
+use std::fmt;
+use std::sync::{Arc, Mutex, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use attach;
+use attach::{AttachArgs, AttachGuard};
+use env::Env;
+use error::{check_result, JniError};
+use ffi;
+use ffi::JavaVM;
+use version::JniVersion;
+
+/// A handle to a Java VM, which can be cloned and sent to other threads.
+///
+/// Each thread gets its own `JNIEnv` by attaching to the VM. All handles to
+/// a VM know when it has been shut down by `JNI::shutdown()` or dropping the
+/// `JNI` instance, and fail with `JniError::NoJavaVm` afterwards. A VM which is
+/// destroyed by other means must not be used through its handles any more.
+#[derive(Clone)]
+pub struct JavaVm {
+    inner: Arc<JavaVmInner>
+}
+
+struct JavaVmInner {
+    jvm: *mut JavaVM,
+    version: JniVersion,
+    alive: AtomicBool,
+    // Keeps the JVM library loaded as long as the VM can be reached.
+    _libjvm: Option<Arc<DynamicLibrary>>
+}
+
+// The invocation interface may be called from any thread.
+unsafe impl Send for JavaVmInner {}
+unsafe impl Sync for JavaVmInner {}
+
+/// The VMs which handles exist for, so that all handles to the same VM share
+/// its liveness.
+static VMS:Mutex<Vec<Weak<JavaVmInner>>> = Mutex::new(Vec::new());
+
+fn shared(jvm:*mut JavaVM, version:JniVersion, libjvm:Option<Arc<DynamicLibrary>>) -> Arc<JavaVmInner> {
+    let mut vms = VMS.lock().unwrap_or_else(|error| error.into_inner());
+    vms.retain(|vm| vm.strong_count() > 0);
+
+    let existing = vms.iter()
+        .filter_map(Weak::upgrade)
+        .find(|vm| vm.jvm == jvm && vm.alive.load(Ordering::Acquire));
+
+    match existing {
+        Some(inner) => inner,
+        None => {
+            let inner = Arc::new(JavaVmInner { jvm, version, alive: AtomicBool::new(true), _libjvm: libjvm });
+            vms.push(Arc::downgrade(&inner));
+            inner
+        }
+    }
+}
+
+impl fmt::Debug for JavaVm {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("JavaVm")
+            .field("jvm", &self.inner.jvm)
+            .field("version", &self.inner.version)
+            .field("alive", &self.is_alive())
+            .finish()
+    }
+}
+
+impl JavaVm {
+    /// Wraps a `JavaVM` pointer, e.g. the one passed to `JNI_OnLoad`.
+    ///
+    /// Returns a clone of an existing handle to the same VM, if there is one.
+    ///
+    /// # Safety
+    ///
+    /// `jvm` must point to a valid Java VM, which supports `version`.
+    pub unsafe fn from_raw(jvm:*mut JavaVM, version:JniVersion) -> JavaVm {
+        JavaVm { inner: shared(jvm, version, None) }
+    }
+
+    pub(crate) fn with_library(jvm:*mut JavaVM, version:JniVersion, libjvm:Arc<DynamicLibrary>) -> JavaVm {
+        JavaVm { inner: shared(jvm, version, Some(libjvm)) }
+    }
+
+    pub fn as_raw(&self) -> *mut JavaVM {
+        self.inner.jvm
+    }
+
+    /// The JNI version used to get the `JNIEnv` of attached threads.
+    pub fn version(&self) -> JniVersion {
+        self.inner.version
+    }
+
+    /// Returns false once the VM is being shut down.
+    pub fn is_alive(&self) -> bool {
+        self.inner.alive.load(Ordering::Acquire)
+    }
+
+    /// Marks the VM as shut down, before `DestroyJavaVM` is called.
+    pub(crate) fn set_destroyed(&self) {
+        self.inner.alive.store(false, Ordering::Release);
+    }
+
+    fn check_alive(&self) -> Result<(), JniError> {
+        match self.is_alive() {
+            true => Ok(()),
+            false => Err(JniError::NoJavaVm)
+        }
+    }
+
+    /// Attaches the current thread. The returned guard detaches the thread
+    /// again when dropped, if it wasn't attached before.
+    pub fn attach_current_thread(&self, args:AttachArgs) -> Result<AttachGuard, JniError> {
+        self.check_alive()?;
+
+        unsafe { AttachGuard::attach(self, &args) }
+    }
+
+    /// Detaches the current thread.
+    ///
+    /// # Safety
+    ///
+    /// No `JNIEnv` or local reference of the current thread may be used
+    /// afterwards, and no `AttachGuard` for it may be alive.
+    pub unsafe fn detach_current_thread(&self) -> Result<(), JniError> {
+        check_result(ffi::detach_current_thread(&*self.inner.jvm))
+    }
+
+    /// Returns true if the current thread is attached to the VM.
+    pub fn is_current_thread_attached(&self) -> bool {
+        self.is_alive() && unsafe { attach::get_env(self.inner.jvm, self.inner.version) }.is_ok()
+    }
+
+    /// Returns the `JNIEnv` of the current thread, or `JniError::Detached` if
+    /// the thread is not attached.
+    ///
+    /// # Safety
+    ///
+    /// No other `Env` of the current thread may be used while the returned one
+    /// is alive. `AttachGuard::env()` and `JNI::env()` hand out an `Env` safely.
+    pub unsafe fn env(&self) -> Result<Env<'_>, JniError> {
+        self.check_alive()?;
+
+        attach::get_env(self.inner.jvm, self.inner.version).map(|env| Env::from_raw(env))
+    }
+
+    /// Returns the `JNIEnv` of the current thread, attaching the thread until
+    /// it exits if needed. See `attach::get_env_or_attach()`.
+    ///
+    /// # Safety
+    ///
+    /// No other `Env` of the current thread may be used while the returned one
+    /// is alive.
+    pub unsafe fn env_or_attach(&self, args:AttachArgs) -> Result<Env<'_>, JniError> {
+        self.check_alive()?;
+
+        attach::get_env_or_attach(self.inner.jvm, &args).map(|env| Env::from_raw(env))
+    }
+}
@@ -0,0 +1,106 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::attach::AttachArgs;
+use jni::env::Env;
+use jni::error::JniError;
+use jni::native::catch_panic;
+use jni::objects::JObject;
+use jni::refs::GlobalRef;
+use jni::types::Jint;
+use std::thread;
+
+fn current_thread<'local>(env:&Env<'local>) -> JObject<'local> {
+    let class = env.find_class("java/lang/Thread").unwrap();
+    let method = env.get_static_method_id(&class, "currentThread", "()Ljava/lang/Thread;").unwrap();
+
+    env.call_static_object_method_a(&class, method, &[]).unwrap().unwrap()
+}
+
+fn thread_group<'local>(env:&Env<'local>) -> JObject<'local> {
+    let thread = current_thread(env);
+    let class = env.get_object_class(&thread).unwrap();
+    let method = env.get_method_id(&class, "getThreadGroup", "()Ljava/lang/ThreadGroup;").unwrap();
+
+    env.call_object_method_a(&thread, method, &[]).unwrap().unwrap()
+}
+
+#[test]
+fn threads_get_the_given_name() {
+    let mut attachment = attach_or_skip!();
+    let vm = attachment.env().unwrap().get_java_vm().unwrap();
+
+    let name = thread::spawn(move || {
+        let mut attachment = vm.attach_current_thread(AttachArgs::new().name("rust-worker")).unwrap();
+        let env = attachment.env().unwrap();
+
+        let thread = current_thread(&env);
+        let class = env.get_object_class(&thread).unwrap();
+        let method = env.get_method_id(&class, "getName", "()Ljava/lang/String;").unwrap();
+        let name = env.call_object_method_a(&thread, method, &[]).unwrap().unwrap();
+
+        env.get_string(&env.downcast(name).unwrap()).unwrap()
+    }).join().unwrap();
+
+    assert_eq!(name, "rust-worker");
+}
+
+#[test]
+fn threads_join_the_given_group() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let class = env.find_class("java/lang/ThreadGroup").unwrap();
+    let ctor = env.get_method_id(&class, "<init>", "(Ljava/lang/String;)V").unwrap();
+    let name = env.new_string("rust-group").unwrap();
+    let group = env.new_object_a(&class, ctor, &[(&name).into()]).unwrap();
+    let group = GlobalRef::new(&env, &group).unwrap();
+
+    let args = AttachArgs::new().group(&env, &group).unwrap();
+    let vm = env.get_java_vm().unwrap();
+
+    let same_group = thread::spawn(move || {
+        let mut attachment = vm.attach_current_thread(args).unwrap();
+        let env = attachment.env().unwrap();
+
+        env.is_same_object(Some(&thread_group(&env)), Some(group.as_obj())).unwrap()
+    }).join().unwrap();
+
+    assert!(same_group);
+}
+
+#[test]
+fn groups_must_be_thread_groups() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let string = env.new_string("not a group").unwrap();
+    let string = GlobalRef::new(&env, &string).unwrap();
+
+    assert!(matches!(AttachArgs::new().group(&env, &string), Err(JniError::WrongType("java/lang/ThreadGroup"))));
+}
+
+#[test]
+fn one_env_per_thread() {
+    let mut attachment = attach_or_skip!();
+    let vm = attachment.env().unwrap().get_java_vm().unwrap();
+    let mut other = vm.attach_current_thread(AttachArgs::new()).unwrap();
+
+    {
+        let env = attachment.env().unwrap();
+        assert!(matches!(other.env(), Err(JniError::EnvInUse)));
+
+        let result:Jint = unsafe {
+            catch_panic(env.as_raw(), |_:&mut Env| {
+                assert!(matches!(other.env(), Err(JniError::EnvInUse)));
+                1
+            })
+        };
+        assert_eq!(result, 1);
+        assert!(matches!(other.env(), Err(JniError::EnvInUse)));
+    }
+
+    assert!(other.env().is_ok());
+}
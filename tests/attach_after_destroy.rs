@@ -0,0 +1,38 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::attach::AttachArgs;
+use jni::error::JniError;
+use jni::JvmBuilder;
+use std::sync::mpsc;
+use std::thread;
+
+#[test]
+fn guards_outliving_the_vm_do_nothing() {
+    skip_without_jvm!();
+
+    let jni = JvmBuilder::new().build().unwrap();
+    let vm = jni.java_vm().unwrap();
+
+    let (attached_sender, attached) = mpsc::channel();
+    let (destroyed_sender, destroyed) = mpsc::channel();
+
+    let worker = thread::spawn(move || {
+        // DestroyJavaVM does not wait for daemon threads.
+        let mut attachment = vm.attach_current_thread(AttachArgs::new().daemon(true)).unwrap();
+        attached_sender.send(()).unwrap();
+
+        destroyed.recv().unwrap();
+        assert!(matches!(attachment.env(), Err(JniError::NoJavaVm)));
+        // Must not detach from the destroyed VM.
+        drop(attachment);
+    });
+
+    attached.recv().unwrap();
+    drop(jni);
+    destroyed_sender.send(()).unwrap();
+
+    worker.join().unwrap();
+}
@@ -0,0 +1,34 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::error::JniError;
+use jni::JvmBuilder;
+
+#[test]
+fn options_with_nul_are_rejected_before_loading() {
+    let result = JvmBuilder::new()
+        .libjvm_path("/nonexistent/libjvm.so")
+        .option("-Dkey=a\0b")
+        .build();
+
+    assert!(matches!(result, Err(JniError::InvalidArgument(_))));
+}
+
+#[test]
+fn unrecognized_options_can_be_ignored() {
+    skip_without_jvm!();
+
+    let result = JvmBuilder::new().option("-Xunrecognized-by-jni-tests").build();
+    // HotSpot reports unrecognized options with JNI_ERR.
+    assert!(matches!(result, Err(JniError::Unknown)), "unexpected result {:?}", result.err());
+
+    let mut jni = JvmBuilder::new()
+        .option("-Xunrecognized-by-jni-tests")
+        .ignore_unrecognized(true)
+        .build()
+        .unwrap();
+
+    jni.env().unwrap().find_class("java/lang/Object").unwrap();
+}
@@ -0,0 +1,105 @@
+//! A Java VM shared by the tests of one test binary
+//!
+//! Tests which need a Java VM are skipped if none can be created, so that
+//! they pass on machines without Java. Set `JNI_TESTS_REQUIRE_JVM` to make
+//! them fail instead, e.g. in CI.
+
+#![allow(dead_code, unused_macros)]
+
+use jni::attach::{AttachArgs, AttachGuard};
+use jni::env::Env;
+use jni::shutdown::DropBehavior;
+use jni::vm::JavaVm;
+use jni::{JvmBuilder, JNI};
+use std::env;
+use std::fmt::Display;
+use std::process::{Command, Output};
+use std::sync::OnceLock;
+
+const REQUIRE_JVM:&str = "JNI_TESTS_REQUIRE_JVM";
+const CHILD:&str = "JNI_TESTS_CHILD";
+
+static VM:OnceLock<Result<JavaVm, String>> = OnceLock::new();
+
+/// Attaches the current thread to the shared Java VM, or returns from the
+/// test if there is none.
+macro_rules! attach_or_skip {
+    () => {
+        match $crate::common::attach() {
+            Some(attachment) => attachment,
+            None => return
+        }
+    }
+}
+
+/// Returns from the test if no JVM library can be found.
+macro_rules! skip_without_jvm {
+    () => {
+        if !$crate::common::jvm_available() {
+            return;
+        }
+    }
+}
+
+/// Reports that a test is skipped for lack of a Java VM. Panics instead if
+/// `JNI_TESTS_REQUIRE_JVM` is set.
+pub fn skip(reason:&dyn Display) {
+    if env::var_os(REQUIRE_JVM).is_some() {
+        panic!("{} is set, but there is no Java VM: {}", REQUIRE_JVM, reason);
+    }
+
+    eprintln!("Skipping test which needs a Java VM: {}", reason);
+}
+
+/// Returns true if a JVM library can be found. See `skip()`.
+pub fn jvm_available() -> bool {
+    match JNI::discover() {
+        Ok(_) => true,
+        Err(err) => {
+            skip(&err);
+            false
+        }
+    }
+}
+
+/// Attaches the current thread to the shared Java VM, which is created on
+/// first use and never destroyed. Returns `None` if the VM cannot be created.
+/// See `skip()`.
+pub fn attach() -> Option<AttachGuard> {
+    let vm = VM.get_or_init(|| {
+        let mut jni = JvmBuilder::new().option("-Xcheck:jni").build().map_err(|err| err.to_string())?;
+        jni.set_drop_behavior(DropBehavior::Leak);
+        jni.java_vm().map_err(|err| err.to_string())
+    });
+
+    match vm {
+        Ok(vm) => Some(vm.attach_current_thread(AttachArgs::new()).unwrap()),
+        Err(err) => {
+            skip(err);
+            None
+        }
+    }
+}
+
+/// Runs the test `name` of the current test binary in a child process, for
+/// tests which end the process. `in_child()` returns true in the child.
+pub fn run_in_child(name:&str) -> Output {
+    Command::new(env::current_exe().unwrap())
+        .args([name, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD, name)
+        .output()
+        .unwrap()
+}
+
+pub fn in_child() -> bool {
+    env::var_os(CHILD).is_some()
+}
+
+/// Runs `System.gc()`, which collects unreachable objects unless explicit
+/// collections are disabled.
+pub fn gc(env:&Env) {
+    let class = env.find_class("java/lang/System").unwrap();
+    let method = env.get_static_method_id(&class, "gc", "()V").unwrap();
+
+    env.call_static_void_method_a(&class, method, &[]).unwrap();
+}
@@ -0,0 +1,30 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::JvmBuilder;
+use jni::JNI;
+use std::thread;
+
+#[test]
+fn created_vms_are_found_and_attached_to() {
+    skip_without_jvm!();
+
+    let mut jni = JvmBuilder::new().build().unwrap();
+    let vm = jni.java_vm().unwrap();
+
+    thread::spawn(|| {
+        let mut other = JNI::discover().unwrap();
+        assert_eq!(other.get_created_java_vms().unwrap().len(), 1);
+
+        other.attach_created_java_vm().unwrap();
+        other.env().unwrap().find_class("java/lang/String").unwrap();
+        // Only detaches this thread.
+        drop(other);
+    }).join().unwrap();
+
+    assert!(vm.is_alive());
+    assert_eq!(jni.get_created_java_vms().unwrap().len(), 1);
+    jni.env().unwrap().find_class("java/lang/String").unwrap();
+}
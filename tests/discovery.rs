@@ -0,0 +1,161 @@
+extern crate jni;
+
+use jni::discovery::{candidates, Candidate, CandidateSource, DiscoveryReport};
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The tests change the environment of the whole process.
+static ENVIRONMENT:Mutex<()> = Mutex::new(());
+
+/// A directory with fake Java homes, which is removed when dropped.
+struct TempHomes {
+    root: PathBuf
+}
+
+impl TempHomes {
+    fn new(name:&str) -> TempHomes {
+        let root = env::temp_dir().join(format!("jni-discovery-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        TempHomes { root: fs::canonicalize(&root).unwrap() }
+    }
+
+    fn home(&self, name:&str) -> PathBuf {
+        let home = self.root.join(name);
+        fs::create_dir_all(home.join("bin")).unwrap();
+        home
+    }
+}
+
+impl Drop for TempHomes {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Sets environment variables until dropped.
+struct Vars {
+    previous: Vec<(&'static str, Option<OsString>)>
+}
+
+impl Vars {
+    fn set(vars:&[(&'static str, &Path)]) -> Vars {
+        let previous = vars.iter().map(|&(name, value)| {
+            let previous = env::var_os(name);
+            env::set_var(name, value);
+            (name, previous)
+        }).collect();
+
+        Vars { previous }
+    }
+}
+
+impl Drop for Vars {
+    fn drop(&mut self) {
+        for (name, value) in self.previous.drain(..) {
+            match value {
+                Some(value) => env::set_var(name, value),
+                None => env::remove_var(name)
+            }
+        }
+    }
+}
+
+/// The sources in the order they first appear.
+fn source_order(candidates:&[(PathBuf, CandidateSource)]) -> Vec<CandidateSource> {
+    let mut order:Vec<CandidateSource> = Vec::new();
+
+    for &(_, source) in candidates {
+        if order.last() != Some(&source) {
+            order.push(source);
+        }
+    }
+
+    order
+}
+
+fn paths_from(candidates:&[(PathBuf, CandidateSource)], source:CandidateSource) -> Vec<PathBuf> {
+    candidates.iter().filter(|candidate| candidate.1 == source).map(|candidate| candidate.0.clone()).collect()
+}
+
+#[test]
+fn candidates_are_ordered_by_source() {
+    let _lock = ENVIRONMENT.lock().unwrap_or_else(|error| error.into_inner());
+    let homes = TempHomes::new("order");
+
+    let java_home = homes.home("java-home");
+    let jdk_home = homes.home("jdk-home");
+    let path_home = homes.home("path-home");
+    fs::write(path_home.join("bin").join("java"), b"").unwrap();
+
+    let _vars = Vars::set(&[("JAVA_HOME", &java_home), ("JDK_HOME", &jdk_home), ("PATH", &path_home.join("bin"))]);
+    let candidates = candidates();
+
+    let mut expected = vec![CandidateSource::JavaHome, CandidateSource::JdkHome, CandidateSource::JavaOnPath];
+    if candidates.iter().any(|candidate| candidate.1 == CandidateSource::SystemInstall) {
+        expected.push(CandidateSource::SystemInstall);
+    }
+    expected.push(CandidateSource::BundledJre);
+
+    assert_eq!(source_order(&candidates), expected);
+    assert!(paths_from(&candidates, CandidateSource::JavaHome).iter().all(|path| path.starts_with(&java_home)));
+    assert!(paths_from(&candidates, CandidateSource::JdkHome).iter().all(|path| path.starts_with(&jdk_home)));
+    assert!(paths_from(&candidates, CandidateSource::JavaOnPath).iter().all(|path| path.starts_with(&path_home)));
+
+    let exe_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    assert!(paths_from(&candidates, CandidateSource::BundledJre).iter().all(|path| path.starts_with(exe_dir.join("jre"))));
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinked_homes_are_tried_once() {
+    let _lock = ENVIRONMENT.lock().unwrap_or_else(|error| error.into_inner());
+    let homes = TempHomes::new("symlink");
+
+    let java_home = homes.home("java-home");
+    let link = homes.root.join("link");
+    std::os::unix::fs::symlink(&java_home, &link).unwrap();
+
+    let _vars = Vars::set(&[("JAVA_HOME", &java_home), ("JDK_HOME", &link), ("PATH", &homes.root)]);
+    let candidates = candidates();
+
+    assert_eq!(paths_from(&candidates, CandidateSource::JavaHome).len(), 3);
+    assert!(paths_from(&candidates, CandidateSource::JdkHome).is_empty());
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[test]
+fn homes_are_searched_in_all_layouts() {
+    let _lock = ENVIRONMENT.lock().unwrap_or_else(|error| error.into_inner());
+    let homes = TempHomes::new("layouts");
+
+    let java_home = homes.home("java-home");
+
+    let _vars = Vars::set(&[("JAVA_HOME", &java_home), ("JDK_HOME", &java_home), ("PATH", &homes.root)]);
+
+    assert_eq!(paths_from(&candidates(), CandidateSource::JavaHome), vec![
+        java_home.join("lib/server/libjvm.so"),
+        java_home.join("jre/lib/amd64/server/libjvm.so"),
+        java_home.join("lib/amd64/server/libjvm.so")
+    ]);
+}
+
+#[test]
+fn reports_list_the_candidates_tried() {
+    assert_eq!(DiscoveryReport::default().to_string(), "No JVM library found: no candidate locations");
+
+    let report = DiscoveryReport {
+        candidates: vec![
+            Candidate { path: PathBuf::from("/opt/java/lib/server/libjvm.so"), source: CandidateSource::JavaHome, error: String::from("not found") },
+            Candidate { path: PathBuf::from("/app/jre/lib/server/libjvm.so"), source: CandidateSource::BundledJre, error: String::from("wrong ELF class") }
+        ]
+    };
+
+    assert_eq!(report.to_string(), "No JVM library found, candidates tried:\n  \
+        /opt/java/lib/server/libjvm.so (JAVA_HOME): not found\n  \
+        /app/jre/lib/server/libjvm.so (bundled JRE): wrong ELF class");
+}
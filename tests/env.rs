@@ -0,0 +1,116 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::error::JniError;
+use jni::objects::{JObject, JString};
+use jni::refs::WeakRef;
+use jni::types::JobjectRefType;
+use jni::value::JValue;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+
+#[test]
+fn names_with_nul_are_rejected() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    assert!(matches!(env.find_class("java/lang\0String"), Err(JniError::InvalidArgument(_))));
+
+    let class = env.find_class("java/lang/String").unwrap();
+    assert!(matches!(env.get_method_id(&class, "length", "()\0I"), Err(JniError::InvalidArgument(_))));
+}
+
+#[test]
+fn booleans_map_to_jboolean() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let class = env.find_class("java/lang/Boolean").unwrap();
+    let xor = env.get_static_method_id(&class, "logicalXor", "(ZZ)Z").unwrap();
+
+    assert!(env.call_static_boolean_method_a(&class, xor, &[JValue::Bool(true), JValue::Bool(false)]).unwrap());
+    assert!(!env.call_static_boolean_method_a(&class, xor, &[JValue::Bool(true), JValue::Bool(true)]).unwrap());
+    assert!(!env.call_static_boolean_method_a(&class, xor, &[JValue::Bool(false), JValue::Bool(false)]).unwrap());
+}
+
+#[test]
+fn calls_are_refused_while_an_exception_is_pending() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    env.throw_new("java/lang/IllegalStateException", "pending").unwrap();
+
+    assert!(matches!(env.find_class("java/lang/Object"), Err(JniError::ExceptionPending)));
+    assert!(env.exception_check());
+
+    env.exception_clear();
+
+    assert!(env.find_class("java/lang/Object").is_ok());
+}
+
+#[test]
+fn primitive_array_names_in_errors() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    match env.new_int_array(-1) {
+        Err(JniError::JavaException(exception)) => assert_eq!(exception.binary_name, "java.lang.NegativeArraySizeException"),
+        other => panic!("unexpected result {:?}", other)
+    }
+}
+
+#[test]
+fn local_frames_keep_only_the_returned_object() {
+    let mut attachment = attach_or_skip!();
+    let mut env = attachment.env().unwrap();
+
+    let mut freed = None;
+    let kept = env.with_local_frame(4, |env| {
+        let string = env.new_string("freed")?;
+        freed = Some(WeakRef::new(env, &string)?);
+        Ok(Some(JObject::from(env.new_string("kept")?)))
+    }).unwrap().unwrap();
+
+    common::gc(&env);
+
+    assert!(freed.unwrap().upgrade_local(&env).unwrap().is_none());
+    assert!(matches!(env.get_object_ref_type(&kept).unwrap(), JobjectRefType::JNILocalRefType));
+
+    let kept:JString = env.downcast(kept).unwrap();
+    assert_eq!(env.get_string(&kept).unwrap(), "kept");
+}
+
+#[test]
+fn local_frames_are_popped_on_panic() {
+    let mut attachment = attach_or_skip!();
+    let mut env = attachment.env().unwrap();
+
+    let mut freed = None;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        env.with_local_frame(4, |env| {
+            let string = env.new_string("freed").unwrap();
+            freed = Some(WeakRef::new(env, &string).unwrap());
+            panic!("in frame")
+        })
+    }));
+    assert!(result.is_err());
+
+    common::gc(&env);
+
+    assert!(freed.unwrap().upgrade_local(&env).unwrap().is_none());
+}
+
+#[test]
+fn local_frames_leave_pending_exceptions_alone() {
+    let mut attachment = attach_or_skip!();
+    let mut env = attachment.env().unwrap();
+
+    env.throw_new("java/lang/IllegalStateException", "pending").unwrap();
+
+    assert!(matches!(env.with_local_frame(4, |_| Ok(None)), Err(JniError::ExceptionPending)));
+    assert!(env.exception_check());
+
+    env.exception_clear();
+}
@@ -0,0 +1,215 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::env::Env;
+use jni::error::JniError;
+use jni::exception::{take_exception, throw_exception, JavaException, ToJavaException};
+use jni::objects::*;
+use std::error::Error;
+use std::io;
+
+fn new_exception<'local>(env:&Env<'local>, class_name:&str, message:&str, cause:Option<&JThrowable>) -> JThrowable<'local> {
+    let class = env.find_class(class_name).unwrap();
+    let message = env.new_string(message).unwrap();
+
+    let obj = match cause {
+        Some(cause) => {
+            let ctor = env.get_method_id(&class, "<init>", "(Ljava/lang/String;Ljava/lang/Throwable;)V").unwrap();
+            env.new_object_a(&class, ctor, &[(&message).into(), (&**cause).into()]).unwrap()
+        },
+        None => {
+            let ctor = env.get_method_id(&class, "<init>", "(Ljava/lang/String;)V").unwrap();
+            env.new_object_a(&class, ctor, &[(&message).into()]).unwrap()
+        }
+    };
+
+    env.downcast(obj).unwrap()
+}
+
+#[test]
+fn thrown_exceptions_are_captured() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let class = env.find_class("java/lang/Integer").unwrap();
+    let parse_int = env.get_static_method_id(&class, "parseInt", "(Ljava/lang/String;)I").unwrap();
+    let input = env.new_string("x").unwrap();
+
+    let exception = match env.call_static_int_method_a(&class, parse_int, &[(&input).into()]) {
+        Err(JniError::JavaException(exception)) => exception,
+        other => panic!("unexpected result {:?}", other)
+    };
+
+    assert!(!env.exception_check());
+    assert_eq!(exception.binary_name, "java.lang.NumberFormatException");
+    assert!(exception.message.as_ref().unwrap().contains("\"x\""));
+    assert!(exception.stack_trace().starts_with("java.lang.NumberFormatException"));
+    assert!(exception.frames().iter().any(|frame| frame.class_name == "java.lang.Integer" && frame.method_name == "parseInt"));
+    assert!(exception.caused_by().is_none());
+    assert!(exception.suppressed().is_empty());
+}
+
+#[test]
+fn causes_are_described() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let root = new_exception(&env, "java/io/IOException", "root", None);
+    let top = new_exception(&env, "java/lang/RuntimeException", "top", Some(&root));
+
+    let exception = JavaException::new(&env, &top).unwrap();
+    assert_eq!(exception.to_string(), "java.lang.RuntimeException: top");
+    assert!(exception.stack_trace().contains("Caused by: java.io.IOException: root"));
+
+    let cause = exception.caused_by().unwrap();
+    assert_eq!(cause.to_string(), "java.io.IOException: root");
+    assert!(cause.caused_by().is_none());
+    assert_eq!(exception.source().unwrap().to_string(), cause.to_string());
+}
+
+#[derive(Debug)]
+struct CustomError {
+    class_name: Option<&'static str>,
+    cause: Option<io::Error>
+}
+
+impl ToJavaException for CustomError {
+    fn class_name(&self) -> &str {
+        self.class_name.unwrap_or("java/lang/RuntimeException")
+    }
+
+    fn message(&self) -> String {
+        String::from("custom")
+    }
+
+    fn cause(&self) -> Option<&dyn ToJavaException> {
+        self.cause.as_ref().map(|cause| cause as &dyn ToJavaException)
+    }
+}
+
+/// A type which only implements the required methods.
+struct PlainError;
+
+impl ToJavaException for PlainError {
+    fn message(&self) -> String {
+        String::from("plain")
+    }
+}
+
+fn throw_and_take(env:&Env, error:&dyn ToJavaException) -> Box<JavaException> {
+    throw_exception(env, error).unwrap();
+    assert!(env.exception_check());
+
+    Box::new(take_exception(env).unwrap().unwrap())
+}
+
+#[test]
+fn io_errors_become_io_exceptions() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let exception = throw_and_take(&env, &io::Error::other("disk full"));
+    assert_eq!(exception.binary_name, "java.io.IOException");
+    assert_eq!(exception.message.as_deref(), Some("disk full"));
+}
+
+#[test]
+fn default_class_is_runtime_exception() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let exception = throw_and_take(&env, &PlainError);
+    assert_eq!(exception.to_string(), "java.lang.RuntimeException: plain");
+}
+
+#[test]
+fn causes_are_thrown_along() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let error = CustomError { class_name: Some("java/lang/IllegalStateException"), cause: Some(io::Error::other("root")) };
+    let exception = throw_and_take(&env, &error);
+    assert_eq!(exception.to_string(), "java.lang.IllegalStateException: custom");
+    assert_eq!(exception.caused_by().unwrap().to_string(), "java.io.IOException: root");
+
+    // The (String) constructor of this class sets a null cause.
+    let error = CustomError { class_name: Some("java/lang/ExceptionInInitializerError"), cause: Some(io::Error::other("root")) };
+    let exception = throw_and_take(&env, &error);
+    assert_eq!(exception.to_string(), "java.lang.ExceptionInInitializerError: custom");
+    assert!(exception.caused_by().is_none());
+}
+
+#[test]
+fn pending_exceptions_are_kept() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    env.throw_new("java/lang/UnsupportedOperationException", "first").unwrap();
+    let exception = throw_and_take(&env, &JniError::ExceptionPending);
+    assert_eq!(exception.to_string(), "java.lang.UnsupportedOperationException: first");
+}
+
+#[test]
+fn java_exceptions_are_thrown_again() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let throwable = new_exception(&env, "java/lang/ArithmeticException", "again", None);
+    let error = JniError::JavaException(Box::new(JavaException::new(&env, &throwable).unwrap()));
+
+    let exception = throw_and_take(&env, &error);
+    assert!(env.is_same_object(Some(exception.throwable.as_obj()), Some(&throwable)).unwrap());
+}
+
+#[test]
+fn all_suppressed_exceptions_are_described() {
+    let mut attachment = attach_or_skip!();
+    let mut env = attachment.env().unwrap();
+
+    let top = new_exception(&env, "java/lang/RuntimeException", "top", None);
+    let throwable_class = env.find_class("java/lang/Throwable").unwrap();
+    let add_suppressed = env.get_method_id(&throwable_class, "addSuppressed", "(Ljava/lang/Throwable;)V").unwrap();
+
+    for index in 0..100 {
+        env.with_local_frame(8, |env| {
+            let suppressed = new_exception(env, "java/lang/IllegalArgumentException", &index.to_string(), None);
+            env.call_void_method_a(&top, add_suppressed, &[(&*suppressed).into()])?;
+            Ok(None)
+        }).unwrap();
+    }
+
+    let exception = JavaException::new(&env, &top).unwrap();
+    assert_eq!(exception.suppressed().len(), 100);
+    assert_eq!(exception.suppressed()[99].to_string(), "java.lang.IllegalArgumentException: 99");
+    assert_eq!(exception.source().unwrap().to_string(), "java.lang.IllegalArgumentException: 0");
+}
+
+/// Delegates to a captured exception, but creates a new one.
+struct Rethrown(JavaException);
+
+impl ToJavaException for Rethrown {
+    fn class_name(&self) -> &str {
+        self.0.class_name()
+    }
+
+    fn message(&self) -> String {
+        format!("rethrown: {}", self.0.message())
+    }
+}
+
+#[test]
+fn java_exception_class_names_are_found() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let throwable = new_exception(&env, "java/io/IOException", "original", None);
+    let captured = JavaException::new(&env, &throwable).unwrap();
+    assert_eq!(captured.binary_name, "java.io.IOException");
+    assert_eq!(ToJavaException::class_name(&captured), "java/io/IOException");
+
+    let exception = throw_and_take(&env, &Rethrown(captured));
+    assert_eq!(exception.binary_name, "java.io.IOException");
+    assert_eq!(exception.message.as_deref(), Some("rethrown: original"));
+}
@@ -0,0 +1,69 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::error::JniError;
+use jni::hooks;
+use jni::hooks::ReplacedHooks;
+use jni::shutdown::ShutdownOptions;
+use jni::types::Jint;
+use jni::JvmBuilder;
+use std::mem::transmute;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+
+static PREVIOUS_EXIT:AtomicI32 = AtomicI32::new(0);
+static PREVIOUS_ABORT:AtomicBool = AtomicBool::new(false);
+
+#[test]
+fn failed_builds_report_messages_and_restore_hooks() {
+    skip_without_jvm!();
+
+    // The hooks in place before the VM is created, called the way the VM
+    // calls them.
+    let exit = hooks::install_exit_hook(Box::new(|code| PREVIOUS_EXIT.store(code, Ordering::SeqCst)), &mut ReplacedHooks::default());
+    let abort = hooks::install_abort_hook(Box::new(|| PREVIOUS_ABORT.store(true, Ordering::SeqCst)), &mut ReplacedHooks::default());
+    let exit:extern "system" fn(Jint) = unsafe { transmute(exit) };
+    let abort:extern "system" fn() = unsafe { transmute(abort) };
+
+    let messages = Arc::new(Mutex::new(String::new()));
+    let sink = messages.clone();
+
+    let result = JvmBuilder::new()
+        .option("-Xmx1x")
+        .vfprintf_hook(move |message| sink.lock().unwrap().push_str(message))
+        .exit_hook(|_| panic!("replaced exit hook called"))
+        .abort_hook(|| panic!("replaced abort hook called"))
+        .build();
+
+    assert!(matches!(result, Err(JniError::InvalidArguments)), "unexpected result {:?}", result.err());
+    assert!(messages.lock().unwrap().contains("Invalid maximum heap size: -Xmx1x\n"));
+
+    // The failed build put the previous hooks back.
+    exit(5);
+    abort();
+
+    assert_eq!(PREVIOUS_EXIT.load(Ordering::SeqCst), 5);
+    assert!(PREVIOUS_ABORT.load(Ordering::SeqCst));
+}
+
+#[test]
+fn runtime_exit_calls_the_exit_hook() {
+    skip_without_jvm!();
+
+    if common::in_child() {
+        let jni = JvmBuilder::new()
+            .exit_hook(|code| println!("exit hook: {}", code))
+            .build()
+            .unwrap();
+
+        let result = jni.shutdown(ShutdownOptions::new().runtime_exit(3));
+        panic!("Runtime.exit() returned {:?}", result);
+    }
+
+    let output = common::run_in_child("runtime_exit_calls_the_exit_hook");
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("exit hook: 3\n"));
+}
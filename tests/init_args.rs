@@ -0,0 +1,26 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::error::JniError;
+use jni::version::JniVersion;
+use jni::JNI;
+
+#[test]
+fn unsupported_versions_are_reported() {
+    skip_without_jvm!();
+    let jni = JNI::discover().unwrap();
+
+    assert!(jni.get_default_java_vm_init_args(JniVersion::V1_8).is_ok());
+    assert!(matches!(jni.get_default_java_vm_init_args(JniVersion::V1_1), Err(JniError::UnsupportedVersion(_))));
+
+    let supported = jni.get_supported_version().unwrap();
+
+    if let Some(newer) = JniVersion::all().iter().find(|version| **version > supported) {
+        match jni.get_default_java_vm_init_args(*newer) {
+            Err(JniError::UnsupportedVersion(version)) => assert_eq!(version, newer.to_jint()),
+            other => panic!("unexpected result {:?}", other.map(|args| args.version))
+        }
+    }
+}
@@ -0,0 +1,33 @@
+extern crate jni;
+
+use jni::mutf8::{from_modified_utf8, to_modified_utf8};
+
+#[test]
+fn ascii_and_bmp() {
+    assert_eq!(to_modified_utf8("abc").as_bytes(), b"abc");
+    assert_eq!(to_modified_utf8("é€").as_bytes(), "é€".as_bytes());
+}
+
+#[test]
+fn nul() {
+    assert_eq!(to_modified_utf8("a\0b").as_bytes(), &[b'a', 0xc0, 0x80, b'b']);
+}
+
+#[test]
+fn supplementary() {
+    // U+1F600 is the surrogate pair D83D DE00.
+    assert_eq!(to_modified_utf8("\u{1f600}").as_bytes(), &[0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80]);
+}
+
+#[test]
+fn decode_round_trip() {
+    for value in &["abc", "é€", "a\0b", "\u{1f600}x"] {
+        assert_eq!(from_modified_utf8(to_modified_utf8(value).as_bytes()), *value);
+    }
+}
+
+#[test]
+fn decode_invalid() {
+    assert_eq!(from_modified_utf8(&[b'a', 0xe0, 0x80]), "a\u{fffd}\u{fffd}");
+    assert_eq!(from_modified_utf8(&[0xed, 0xa0, 0xbd]), "\u{fffd}");
+}
@@ -0,0 +1,80 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::consts::*;
+use jni::env::Env;
+use jni::exception::take_exception;
+use jni::native::{catch_panic, catch_result, NativeReturn};
+use jni::types::*;
+use std::io;
+use std::panic;
+
+/// Runs `f` as the body of a native method and returns the message of the
+/// exception it threw.
+fn panic_message<F:FnOnce()>(env:&mut Env, f:F) -> String {
+    let result:Jint = unsafe { catch_panic(env.as_raw(), |_:&mut Env| { f(); 1 }) };
+    assert_eq!(result, 0);
+
+    let exception = take_exception(env).unwrap().unwrap();
+    assert_eq!(exception.binary_name, "java.lang.RuntimeException");
+    exception.message.unwrap()
+}
+
+#[test]
+fn default_values_are_zero() {
+    <() as NativeReturn>::default_value();
+    assert_eq!(Jboolean::default_value(), JNI_FALSE);
+    assert_eq!(Jbyte::default_value(), 0);
+    assert_eq!(Jchar::default_value(), 0);
+    assert_eq!(Jshort::default_value(), 0);
+    assert_eq!(Jint::default_value(), 0);
+    assert_eq!(Jlong::default_value(), 0);
+    assert_eq!(Jfloat::default_value(), 0.0);
+    assert_eq!(Jdouble::default_value(), 0.0);
+    assert!(Jobject::default_value().is_null());
+}
+
+#[test]
+fn panic_payloads_become_messages() {
+    let mut attachment = attach_or_skip!();
+    let mut env = attachment.env().unwrap();
+
+    let message = panic_message(&mut env, || panic!("static message"));
+    assert!(message.starts_with("Rust panic: static message\nat "));
+    assert!(message.contains("tests/native.rs"));
+
+    let value = 42;
+    let message = panic_message(&mut env, || panic!("formatted {}", value));
+    assert!(message.starts_with("Rust panic: formatted 42\n"));
+
+    let message = panic_message(&mut env, || panic::panic_any(value));
+    assert!(message.starts_with("Rust panic: Box<dyn Any>\n"));
+}
+
+#[test]
+fn results_are_returned_without_exception() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let result:Jlong = unsafe { catch_panic(env.as_raw(), |_:&mut Env| 7) };
+    assert_eq!(result, 7);
+    assert!(take_exception(&env).unwrap().is_none());
+}
+
+#[test]
+fn errors_are_thrown_by_catch_result() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let result:Jint = unsafe { catch_result(env.as_raw(), |_:&mut Env| Err(io::Error::other("closed"))) };
+    assert_eq!(result, 0);
+
+    let exception = take_exception(&env).unwrap().unwrap();
+    assert_eq!(exception.to_string(), "java.io.IOException: closed");
+
+    let result:Jint = unsafe { catch_result(env.as_raw(), |_:&mut Env| Ok::<_, io::Error>(3)) };
+    assert_eq!(result, 3);
+    assert!(!env.exception_check());
+}
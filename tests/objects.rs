@@ -0,0 +1,82 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::error::JniError;
+use jni::objects::{AutoLocal, JObject};
+use jni::refs::WeakRef;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn auto_locals_are_deleted_on_drop() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let string = AutoLocal::new(&env, env.new_string("deleted").unwrap());
+    let weak = WeakRef::new(&env, &string).unwrap();
+    drop(string);
+
+    common::gc(&env);
+
+    assert!(weak.upgrade_local(&env).unwrap().is_none());
+}
+
+#[test]
+fn forgotten_auto_locals_stay_alive() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let string = AutoLocal::new(&env, env.new_string("kept").unwrap());
+    let weak = WeakRef::new(&env, &string).unwrap();
+    let string = string.forget();
+
+    common::gc(&env);
+
+    assert!(weak.upgrade_local(&env).unwrap().is_some());
+    assert_eq!(env.get_string(&string).unwrap(), "kept");
+}
+
+static DROPS:AtomicUsize = AtomicUsize::new(0);
+
+struct Counted<'local>(JObject<'local>);
+
+impl<'local> From<Counted<'local>> for JObject<'local> {
+    fn from(counted:Counted<'local>) -> JObject<'local> {
+        unsafe { JObject::from_raw(counted.0.as_raw()) }.unwrap()
+    }
+}
+
+impl<'local> Drop for Counted<'local> {
+    fn drop(&mut self) {
+        DROPS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn auto_locals_drop_their_object_once() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    drop(AutoLocal::new(&env, Counted(env.new_string("counted").unwrap().into())));
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+
+    let counted = AutoLocal::new(&env, Counted(env.new_string("counted").unwrap().into())).forget();
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+
+    drop(counted);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn local_capacity_is_limited() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    env.ensure_local_capacity(64).unwrap();
+
+    match env.ensure_local_capacity(i32::MAX) {
+        Err(JniError::LocalCapacity(capacity)) => assert_eq!(capacity, i32::MAX),
+        other => panic!("unexpected result {:?}", other)
+    }
+}
@@ -0,0 +1,74 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::refs::{GlobalRef, WeakRef};
+use std::thread;
+
+fn assert_send_sync<T:Send + Sync>() {}
+
+#[test]
+fn global_ref_is_send_and_sync() {
+    assert_send_sync::<GlobalRef>();
+}
+
+#[test]
+fn global_refs_are_deleted_on_unattached_threads() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let string = env.new_string("global").unwrap();
+    let global = GlobalRef::new(&env, &string).unwrap();
+    let weak = WeakRef::new(&env, &string).unwrap();
+    env.delete_local_ref(string.into());
+
+    let vm = env.get_java_vm().unwrap();
+    thread::spawn(move || {
+        drop(global);
+        // The thread was only attached while deleting the reference.
+        assert!(!vm.is_current_thread_attached());
+    }).join().unwrap();
+
+    common::gc(&env);
+
+    assert!(weak.is_collected(&env).unwrap());
+}
+
+#[test]
+fn weak_ref_is_send_and_sync() {
+    assert_send_sync::<WeakRef>();
+}
+
+#[test]
+fn weak_refs_upgrade_while_the_object_is_alive() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let string = env.new_string("alive").unwrap();
+    let weak = WeakRef::new(&env, &string).unwrap();
+
+    common::gc(&env);
+
+    let local = weak.upgrade_local(&env).unwrap().unwrap();
+    assert!(env.is_same_object(Some(&local), Some(&string)).unwrap());
+
+    let global = weak.upgrade_global(&env).unwrap().unwrap();
+    assert!(env.is_same_object(Some(global.as_obj()), Some(&string)).unwrap());
+}
+
+#[test]
+fn weak_refs_do_not_upgrade_after_collection() {
+    let mut attachment = attach_or_skip!();
+    let env = attachment.env().unwrap();
+
+    let string = env.new_string("collected").unwrap();
+    let weak = WeakRef::new(&env, &string).unwrap();
+    env.delete_local_ref(string.into());
+
+    common::gc(&env);
+
+    assert!(weak.is_collected(&env).unwrap());
+    assert!(weak.upgrade_local(&env).unwrap().is_none());
+    assert!(weak.upgrade_global(&env).unwrap().is_none());
+}
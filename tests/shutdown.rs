@@ -0,0 +1,182 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::attach::AttachArgs;
+use jni::error::JniError;
+use jni::shutdown::{DropBehavior, ShutdownOptions};
+use jni::JvmBuilder;
+use std::time::Duration;
+
+/// Runs the test `name` in a child process, which must exit with `code`.
+/// Each child creates its own Java VM.
+fn assert_child_exits(name:&str, code:i32) {
+    let output = common::run_in_child(name);
+
+    assert_eq!(output.status.code(), Some(code), "{} exited with {}:\n{}{}", name, output.status,
+        String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+
+    if code == 0 {
+        assert!(String::from_utf8_lossy(&output.stdout).contains("test result: ok. 1 passed"), "{} did not run", name);
+    }
+}
+
+#[test]
+fn shutdown_destroys_the_vm() {
+    skip_without_jvm!();
+
+    if !common::in_child() {
+        return assert_child_exits("shutdown_destroys_the_vm", 0);
+    }
+
+    let jni = JvmBuilder::new().build().unwrap();
+    let vm = jni.java_vm().unwrap();
+
+    jni.shutdown(ShutdownOptions::new()).unwrap();
+
+    assert!(!vm.is_alive());
+    assert!(matches!(vm.attach_current_thread(AttachArgs::new()), Err(JniError::NoJavaVm)));
+}
+
+#[test]
+fn shutdown_times_out_while_java_threads_run() {
+    skip_without_jvm!();
+
+    if !common::in_child() {
+        return assert_child_exits("shutdown_times_out_while_java_threads_run", 0);
+    }
+
+    let mut jni = JvmBuilder::new().build().unwrap();
+
+    {
+        // A `Timer` keeps a non-daemon thread running until it is cancelled.
+        let env = jni.env().unwrap();
+        let class = env.find_class("java/util/Timer").unwrap();
+        let ctor = env.get_method_id(&class, "<init>", "()V").unwrap();
+        env.new_object_a(&class, ctor, &[]).unwrap();
+    }
+
+    let timeout = Duration::from_millis(200);
+
+    match jni.shutdown(ShutdownOptions::new().timeout(timeout)) {
+        Err(JniError::ShutdownTimeout(elapsed)) => assert_eq!(elapsed, timeout),
+        other => panic!("unexpected result {:?}", other)
+    }
+}
+
+#[test]
+fn shutdown_calls_runtime_exit() {
+    skip_without_jvm!();
+
+    if !common::in_child() {
+        return assert_child_exits("shutdown_calls_runtime_exit", 5);
+    }
+
+    let jni = JvmBuilder::new().build().unwrap();
+    let result = jni.shutdown(ShutdownOptions::new().runtime_exit(5));
+    panic!("Runtime.exit() returned {:?}", result);
+}
+
+#[test]
+fn shutdown_calls_runtime_halt() {
+    skip_without_jvm!();
+
+    if !common::in_child() {
+        return assert_child_exits("shutdown_calls_runtime_halt", 4);
+    }
+
+    let jni = JvmBuilder::new().build().unwrap();
+    let result = jni.shutdown(ShutdownOptions::new().runtime_halt(4));
+    panic!("Runtime.halt() returned {:?}", result);
+}
+
+#[test]
+fn shutdown_destroys_the_vm_if_runtime_fails() {
+    skip_without_jvm!();
+
+    if !common::in_child() {
+        return assert_child_exits("shutdown_destroys_the_vm_if_runtime_fails", 0);
+    }
+
+    let mut jni = JvmBuilder::new().build().unwrap();
+    let vm = jni.java_vm().unwrap();
+
+    // Runtime cannot be called while an exception is pending.
+    jni.env().unwrap().throw_new("java/lang/IllegalStateException", "pending").unwrap();
+
+    assert!(matches!(jni.shutdown(ShutdownOptions::new().runtime_exit(5)), Err(JniError::ExceptionPending)));
+    assert!(!vm.is_alive());
+}
+
+#[test]
+fn dropping_destroys_the_vm_by_default() {
+    skip_without_jvm!();
+
+    if !common::in_child() {
+        return assert_child_exits("dropping_destroys_the_vm_by_default", 0);
+    }
+
+    let jni = JvmBuilder::new().build().unwrap();
+    let vm = jni.java_vm().unwrap();
+
+    drop(jni);
+
+    assert!(!vm.is_alive());
+}
+
+#[test]
+fn dropping_can_leak_the_vm() {
+    skip_without_jvm!();
+
+    if !common::in_child() {
+        return assert_child_exits("dropping_can_leak_the_vm", 0);
+    }
+
+    let mut jni = JvmBuilder::new().build().unwrap();
+    jni.set_drop_behavior(DropBehavior::Leak);
+    let vm = jni.java_vm().unwrap();
+
+    drop(jni);
+
+    assert!(vm.is_alive());
+    let mut attachment = vm.attach_current_thread(AttachArgs::new()).unwrap();
+    attachment.env().unwrap().find_class("java/lang/Object").unwrap();
+}
+
+#[test]
+fn dropping_can_halt_the_vm() {
+    skip_without_jvm!();
+
+    if !common::in_child() {
+        return assert_child_exits("dropping_can_halt_the_vm", 6);
+    }
+
+    let mut jni = JvmBuilder::new().build().unwrap();
+    jni.set_drop_behavior(DropBehavior::Halt(6));
+
+    drop(jni);
+    panic!("Runtime.halt() returned");
+}
+
+#[test]
+fn exceptions_keep_their_stack_trace_after_shutdown() {
+    skip_without_jvm!();
+
+    if !common::in_child() {
+        return assert_child_exits("exceptions_keep_their_stack_trace_after_shutdown", 0);
+    }
+
+    let mut jni = JvmBuilder::new().build().unwrap();
+
+    let exception = match jni.env().unwrap().find_class("does/not/Exist") {
+        Err(JniError::JavaException(exception)) => exception,
+        other => panic!("unexpected result {:?}", other.map(|_| ()))
+    };
+
+    jni.shutdown(ShutdownOptions::new()).unwrap();
+
+    assert!(exception.stack_trace().starts_with("java.lang.NoClassDefFoundError: does/not/Exist"));
+    // The lazy details cannot be collected any more.
+    assert!(exception.frames().is_empty());
+}
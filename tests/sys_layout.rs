@@ -0,0 +1,77 @@
+extern crate jni;
+
+use jni::sys::{JNIInvokeInterface, JNINativeInterface};
+use std::mem::{offset_of, size_of};
+
+const SLOT:usize = size_of::<*mut u8>();
+
+/// Asserts the function table index of each slot, as listed in the JNI
+/// specification for the respective function.
+macro_rules! assert_slots {
+    ($table:ty, $($field:ident => $index:expr),*) => {
+        $(
+            assert_eq!(offset_of!($table, $field), $index * SLOT, "slot {}", stringify!($field));
+        )*
+    }
+}
+
+#[test]
+fn native_interface_slots() {
+    assert_slots!(JNINativeInterface,
+        reserved0 => 0,
+        reserved3 => 3,
+        get_version => 4,
+        define_class => 5,
+        find_class => 6,
+        from_reflected_method => 7,
+        throw => 13,
+        throw_new => 14,
+        exception_occurred => 15,
+        push_local_frame => 19,
+        new_global_ref => 21,
+        new_object_a => 30,
+        get_method_id => 33,
+        call_object_method_a => 36,
+        call_void_method_a => 63,
+        call_nonvirtual_object_method => 64,
+        get_field_id => 94,
+        get_static_method_id => 113,
+        call_static_void_method_a => 143,
+        get_static_field_id => 144,
+        new_string => 163,
+        new_string_utf => 167,
+        get_array_length => 171,
+        new_object_array => 172,
+        register_natives => 215,
+        monitor_enter => 217,
+        get_java_vm => 219,
+        get_string_region => 220,
+        get_primitive_array_critical => 222,
+        new_weak_global_ref => 226,
+        exception_check => 228,
+        new_direct_byte_buffer => 229,
+        get_object_ref_type => 232,
+        get_module => 233,
+        is_virtual_thread => 234,
+        get_string_utf_length_as_long => 235
+    );
+}
+
+#[test]
+fn native_interface_size() {
+    assert_eq!(size_of::<JNINativeInterface>(), 236 * SLOT);
+}
+
+#[test]
+fn invoke_interface_slots() {
+    assert_slots!(JNIInvokeInterface,
+        reserved0 => 0,
+        destroy_java_vm => 3,
+        attach_current_thread => 4,
+        detach_current_thread => 5,
+        get_env => 6,
+        attach_current_thread_as_daemon => 7
+    );
+
+    assert_eq!(size_of::<JNIInvokeInterface>(), 8 * SLOT);
+}
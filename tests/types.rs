@@ -0,0 +1,80 @@
+extern crate jni;
+
+use jni::sys::{JavaVMAttachArgs, JavaVMInitArgs, JavaVMOption};
+use jni::objects::{JClass, JObject};
+use jni::types::*;
+use std::mem::{align_of, offset_of, size_of};
+
+const POINTER:usize = size_of::<*mut u8>();
+
+macro_rules! assert_layout {
+    ($($ty:ty => $size:expr),*) => {
+        $(
+            assert_eq!(size_of::<$ty>(), $size, "size of {}", stringify!($ty));
+            assert_eq!(align_of::<$ty>(), $size, "alignment of {}", stringify!($ty));
+        )*
+    }
+}
+
+#[test]
+fn primitive_types() {
+    assert_layout!(
+        Jboolean => 1,
+        Jbyte => 1,
+        Jchar => 2,
+        Jshort => 2,
+        Jint => 4,
+        Jsize => 4,
+        Jfloat => 4,
+        Jdouble => 8
+    );
+
+    // jlong is 8 bytes wide everywhere, but only 4-byte aligned on i686
+    assert_eq!(size_of::<Jlong>(), 8);
+}
+
+#[test]
+fn reference_types() {
+    assert_layout!(
+        Jobject => POINTER,
+        Jclass => POINTER,
+        Jstring => POINTER,
+        JobjectArray => POINTER,
+        Jweak => POINTER,
+        JfieldID => POINTER,
+        JmethodID => POINTER
+    );
+}
+
+#[test]
+fn reference_wrappers() {
+    assert_eq!(size_of::<JObject>(), POINTER);
+    assert_eq!(size_of::<Option<JObject>>(), POINTER);
+    assert_eq!(size_of::<Option<JClass>>(), POINTER);
+}
+
+#[test]
+fn jvalue_union() {
+    assert_eq!(size_of::<Jvalue>(), 8);
+    assert_eq!(size_of::<Jvalue>() % align_of::<Jlong>(), 0);
+}
+
+#[test]
+fn invocation_structs() {
+    assert_eq!(size_of::<JavaVMOption>(), 2 * POINTER);
+
+    assert_eq!(offset_of!(JavaVMInitArgs, version), 0);
+    assert_eq!(offset_of!(JavaVMInitArgs, n_options), 4);
+    assert_eq!(offset_of!(JavaVMInitArgs, options), 8);
+    assert_eq!(offset_of!(JavaVMInitArgs, ignore_unrecognized), 8 + POINTER);
+
+    assert_eq!(offset_of!(JavaVMAttachArgs, version), 0);
+    assert_eq!(offset_of!(JavaVMAttachArgs, name), POINTER.max(4));
+    assert_eq!(offset_of!(JavaVMAttachArgs, group), 2 * POINTER.max(4));
+}
+
+#[test]
+fn constants() {
+    assert_eq!(jni::consts::JNI_EDETACHED, -2);
+    assert!(jni::consts::JNI_NULL.is_null());
+}
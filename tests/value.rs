@@ -0,0 +1,62 @@
+extern crate jni;
+
+use jni::consts::*;
+use jni::objects::JObject;
+use jni::types::*;
+use jni::value::JValue;
+use std::ptr::NonNull;
+
+/// A non-null reference which is never passed to a VM.
+fn fake_object<'local>() -> JObject<'local> {
+    let raw:Jobject = NonNull::dangling().as_ptr();
+    unsafe { JObject::from_raw(raw) }.unwrap()
+}
+
+#[test]
+fn primitives_round_trip() {
+    unsafe {
+        assert_eq!(JValue::from(true).to_jvalue().z, JNI_TRUE);
+        assert_eq!(JValue::from(false).to_jvalue().z, JNI_FALSE);
+        assert_eq!(JValue::from(-8 as Jbyte).to_jvalue().b, -8);
+        assert_eq!(JValue::from(0xffff as Jchar).to_jvalue().c, 0xffff);
+        assert_eq!(JValue::from(-16 as Jshort).to_jvalue().s, -16);
+        assert_eq!(JValue::from(Jint::MIN).to_jvalue().i, Jint::MIN);
+        assert_eq!(JValue::from(Jlong::MAX).to_jvalue().j, Jlong::MAX);
+        assert_eq!(JValue::from(1.5 as Jfloat).to_jvalue().f, 1.5);
+        assert_eq!(JValue::from(-0.25 as Jdouble).to_jvalue().d, -0.25);
+    }
+}
+
+#[test]
+fn from_selects_variant() {
+    assert!(matches!(JValue::from(true), JValue::Bool(true)));
+    assert!(matches!(JValue::from(1 as Jbyte), JValue::Byte(1)));
+    assert!(matches!(JValue::from(2 as Jchar), JValue::Char(2)));
+    assert!(matches!(JValue::from(3 as Jshort), JValue::Short(3)));
+    assert!(matches!(JValue::from(4 as Jint), JValue::Int(4)));
+    assert!(matches!(JValue::from(5 as Jlong), JValue::Long(5)));
+    assert!(matches!(JValue::from(6.0 as Jfloat), JValue::Float(value) if value == 6.0));
+    assert!(matches!(JValue::from(7.0 as Jdouble), JValue::Double(value) if value == 7.0));
+
+    let obj = fake_object();
+    assert!(matches!(JValue::from(&obj), JValue::Object(Some(value)) if value.as_raw() == obj.as_raw()));
+}
+
+#[test]
+fn objects_round_trip() {
+    let obj = fake_object();
+
+    unsafe {
+        assert_eq!(JValue::from(&obj).to_jvalue().l, obj.as_raw());
+        assert!(JValue::Object(None).to_jvalue().l.is_null());
+    }
+}
+
+#[test]
+fn into_jvalue_matches_to_jvalue() {
+    let value:Jvalue = JValue::Long(-1).into();
+    assert_eq!(unsafe { value.j }, -1);
+
+    let value:Jvalue = JValue::Object(None).into();
+    assert!(unsafe { value.l }.is_null());
+}
@@ -0,0 +1,28 @@
+extern crate jni;
+
+use jni::version::JniVersion;
+
+#[test]
+fn versions_round_trip() {
+    for version in JniVersion::all() {
+        assert_eq!(JniVersion::from_jint(version.to_jint()), Some(*version));
+        assert_eq!(JniVersion::supported_by(version.to_jint()), Some(*version));
+    }
+
+    assert_eq!(JniVersion::from_jint(0x00190000), None);
+}
+
+#[test]
+fn newer_versions_support_all_known_ones() {
+    assert_eq!(JniVersion::supported_by(0x00190000), Some(JniVersion::V24));
+    assert_eq!(JniVersion::supported_by(0x00160000), Some(JniVersion::V21));
+    assert_eq!(JniVersion::supported_by(0x00010000), None);
+}
+
+#[test]
+fn versions_are_displayed_like_java() {
+    assert_eq!(JniVersion::V1_1.to_string(), "1.1");
+    assert_eq!(JniVersion::V1_8.to_string(), "1.8");
+    assert_eq!(JniVersion::V9.to_string(), "9");
+    assert_eq!(JniVersion::V21.to_string(), "21");
+}
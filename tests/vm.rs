@@ -0,0 +1,31 @@
+extern crate jni;
+
+#[macro_use]
+mod common;
+
+use jni::attach::AttachArgs;
+use jni::error::JniError;
+use jni::vm::JavaVm;
+use std::thread;
+
+fn assert_send_sync_clone<T:Send + Sync + Clone>() {}
+
+#[test]
+fn java_vm_is_shareable() {
+    assert_send_sync_clone::<JavaVm>();
+}
+
+#[test]
+fn unattached_threads_have_no_env() {
+    let mut attachment = attach_or_skip!();
+    let vm = attachment.env().unwrap().get_java_vm().unwrap();
+
+    thread::spawn(move || {
+        assert!(!vm.is_current_thread_attached());
+        assert!(matches!(unsafe { vm.env() }, Err(JniError::Detached)));
+
+        let _attachment = vm.attach_current_thread(AttachArgs::new()).unwrap();
+        assert!(vm.is_current_thread_attached());
+        assert!(unsafe { vm.env() }.is_ok());
+    }).join().unwrap();
+}